@@ -1,46 +1,374 @@
 // src/archive.rs
 
-use crate::decoder::read_general_block_header;
+use crate::crypto::{incorrect_password, password_required, read_encrypted_header, CbcDecryptor, Rar5Keys, KEY_SIZE, SALT_SIZE};
+use crate::decoder::{read_checked_block_header, read_signature};
+use crate::decompression::{get_decompressor, DecompressionError, Decompressor, DummyDecompressor};
+use crate::entry_reader::EntryReader;
+use crate::integrity::EntryVerifier;
+use crate::volume::VolumeSource;
+use crate::password::{Password, PasswordCancelled, PasswordProvider, PasswordRequest, PasswordResponse, PasswordTarget};
+use crate::signature::RarFormat;
 use crate::structures::base::BlockHeader;
 use crate::structures::block::Block;
+use crate::structures::encryption_header::EncryptionHeader;
 use crate::structures::end_of_archive_header::EndOfArchiveHeader;
 use crate::structures::file_header::FileHeader;
+use crate::structures::general_block_header::GeneralBlockHeader;
 use crate::structures::main_archive_header::MainArchiveHeader;
-use std::io::{self, Read};
+use crate::structures::service_header::ServiceHeader;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 use log::debug;
 
+/// How `Archive` reacts to a block header whose CRC32 does not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrcMode {
+    /// Fail with an `InvalidData` error.
+    Strict,
+    /// Return the block; the mismatch is reported by `GeneralBlockHeader::has_crc_mismatch`.
+    #[default]
+    Lenient,
+}
+
+/// How many wrong passwords are accepted for one target before giving up.
+const DEFAULT_MAX_PASSWORD_ATTEMPTS: u32 = 3;
+
 pub struct Archive<R: Read> {
     reader: R,
+    crc_mode: CrcMode,
+    verify_data: bool,
     current_block_data_size: u64,
+    is_solid: bool,
+    /// The decoder state shared by the entries of the current solid stream.
+    solid_decompressor: Option<Box<dyn Decompressor>>,
+    /// Why a skipped entry broke the current solid stream, returned when the
+    /// next solid entry is opened.
+    solid_error: Option<io::Error>,
+    /// The entry whose data area is next in the stream.
+    pending_entry: Option<FileHeader>,
+    password: Option<Password>,
+    password_provider: Option<Box<dyn PasswordProvider>>,
+    max_password_attempts: u32,
+    /// The keys for block headers, with the salt and KDF count they were
+    /// derived from. Volumes usually share them.
+    header_keys: Option<([u8; SALT_SIZE], u8, Rar5Keys)>,
+    /// Whether the current volume's headers after its encryption header are
+    /// encrypted.
+    headers_encrypted: bool,
+    /// The keys of the last encrypted entry, with the salt and KDF count they
+    /// were derived from. Entries usually share them.
+    file_keys: Option<([u8; SALT_SIZE], u8, Rar5Keys)>,
+    /// Opens the volumes after the first one of a multi-volume archive.
+    volumes: Option<Box<dyn VolumeSource<Reader = R>>>,
+    /// The index of the volume being read, counting from 0.
+    volume_index: u64,
+    /// Moves the reader past a data area without reading it, for readers
+    /// that can seek.
+    data_skipper: Option<fn(&mut R, u64) -> io::Result<()>>,
 }
 
 impl<R: Read> Archive<R> {
     pub fn new(mut reader: R) -> io::Result<Self> {
-        let mut signature = [0u8; 8];
-        reader.read_exact(&mut signature)?;
-        // TODO: Add signature validation.
+        if read_signature(&mut reader)? != RarFormat::Rar5 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "RAR 1.5-4.x archives are not supported",
+            ));
+        }
 
         Ok(Archive {
             reader,
+            crc_mode: CrcMode::default(),
+            verify_data: true,
             current_block_data_size: 0,
+            is_solid: false,
+            solid_decompressor: None,
+            solid_error: None,
+            pending_entry: None,
+            password: None,
+            password_provider: None,
+            max_password_attempts: DEFAULT_MAX_PASSWORD_ATTEMPTS,
+            header_keys: None,
+            headers_encrypted: false,
+            file_keys: None,
+            volumes: None,
+            volume_index: 0,
+            data_skipper: None,
         })
     }
 
+    /// Opens a multi-volume archive, starting with volume 0 of `volumes`.
+    ///
+    /// Volume boundaries are hidden: the iterator yields each entry once and
+    /// `read_file_data` and `entry_reader` join data split across volumes.
+    pub fn from_volumes<S: VolumeSource<Reader = R> + 'static>(mut volumes: S) -> io::Result<Self> {
+        let mut archive = Archive::new(volumes.open_volume(0)?)?;
+        archive.volumes = Some(Box::new(volumes));
+        Ok(archive)
+    }
+
+    pub fn set_crc_mode(&mut self, crc_mode: CrcMode) {
+        self.crc_mode = crc_mode;
+    }
+
+    /// Sets the password used to decrypt encrypted headers and entries. It is
+    /// tried before asking the password provider, if one is set.
+    pub fn set_password<P: Into<Password>>(&mut self, password: P) {
+        self.password = Some(password.into());
+    }
+
+    /// Sets the provider asked for a password when one is needed and the
+    /// password from `set_password` is missing or wrong.
+    pub fn set_password_provider<P: PasswordProvider + 'static>(&mut self, provider: P) {
+        self.password_provider = Some(Box::new(provider));
+    }
+
+    /// Sets how many wrong passwords are accepted for the headers or for one
+    /// entry before failing with an incorrect password error. Defaults to 3.
+    pub fn set_max_password_attempts(&mut self, max_password_attempts: u32) {
+        self.max_password_attempts = max_password_attempts;
+    }
+
+    /// Controls whether `read_file_data` checks unpacked data against the
+    /// CRC32 and hash stored in the file header. Enabled by default.
+    pub fn set_verify_data(&mut self, verify_data: bool) {
+        self.verify_data = verify_data;
+    }
+
+    /// Reads and unpacks the whole data of the entry the iterator just
+    /// returned. Use `entry_reader` to stream large entries instead.
     pub fn read_file_data(&mut self, file_header: &FileHeader) -> io::Result<Vec<u8>> {
-        if let Some(compressed_size) = file_header.base.general_header.data_size {
-            let mut compressed_data = vec![0; compressed_size as usize];
-            self.reader.read_exact(&mut compressed_data)?;
-            self.current_block_data_size = 0; // Data is consumed.
-
-            file_header
-                .decompress(&compressed_data)
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Decompression error: {:?}", e)))
+        if file_header.base.general_header.data_size.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut data = Vec::new();
+        self.entry_reader(file_header)?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Returns a reader that streams the unpacked data of the entry the
+    /// iterator just returned, decoding it chunk by chunk. Data split across
+    /// volumes is followed into the next volumes.
+    ///
+    /// The checksums are verified when the reader reaches the end of the data.
+    /// Dropping it early leaves the rest of the data to be skipped, and breaks
+    /// the solid stream for the entries after it.
+    pub fn entry_reader(&mut self, file_header: &FileHeader) -> io::Result<EntryReader<'_, R>> {
+        self.pending_entry = None;
+        let verify_data = self.verify_data;
+        self.open_entry(file_header, true, verify_data)
+    }
+
+    /// Reads and unpacks the data of a service header, such as the archive
+    /// comment. Service data never belongs to the solid stream.
+    pub fn read_service_data(&mut self, service_header: &ServiceHeader) -> io::Result<Vec<u8>> {
+        let file_header = &service_header.file_header;
+        if file_header.base.general_header.data_size.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut data = Vec::new();
+        let verify_data = self.verify_data;
+        self.open_entry(file_header, false, verify_data)?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Sets up decryption, decompression and verification for an entry whose
+    /// data is next in the stream. `in_solid_stream` is false for service
+    /// data, which is always decoded on its own.
+    fn open_entry(&mut self, header: &FileHeader, in_solid_stream: bool, verify: bool) -> io::Result<EntryReader<'_, R>> {
+        if in_solid_stream && !header.compression_info.is_stored() {
+            if !header.compression_info.is_solid() {
+                // A new solid stream starts here.
+                self.solid_error = None;
+            } else if let Some(error) = self.solid_error.take() {
+                return Err(error);
+            }
+        }
+        let (decryptor, mac_key) = match self.entry_decryption(header, true)? {
+            Some((decryptor, mac_key)) => (Some(decryptor), mac_key),
+            None => (None, None),
+        };
+
+        let compression_info = header.compression_info;
+        let (mut decompressor, keeps_solid_state) = if compression_info.is_stored() {
+            (Box::<DummyDecompressor>::default() as Box<dyn Decompressor>, false)
+        } else if !in_solid_stream {
+            (get_decompressor(compression_info).map_err(decompression_error)?, false)
+        } else if compression_info.is_solid() {
+            let solid_decompressor = self.solid_decompressor.take().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Solid entry cannot be decoded without the entries before it",
+                )
+            })?;
+            (solid_decompressor, self.is_solid)
+        } else {
+            (get_decompressor(compression_info).map_err(decompression_error)?, self.is_solid)
+        };
+        decompressor.start_entry();
+
+        let verifier = if verify {
+            let mut verifier = EntryVerifier::new(header)?;
+            if let Some(mac_key) = mac_key {
+                verifier = verifier.with_mac_key(mac_key);
+            }
+            Some(verifier)
         } else {
-            Ok(Vec::new())
+            None
+        };
+        // Encrypted data is padded to the AES block size.
+        let output_limit = decryptor.as_ref().and(header.unpacked_size);
+        Ok(EntryReader::new(
+            self,
+            header.clone(),
+            decompressor,
+            keeps_solid_state,
+            decryptor,
+            verifier,
+            output_limit,
+        ))
+    }
+
+    /// Derives or reuses the keys of an encrypted entry and returns the
+    /// decryptor for its data, with the key for checksums stored as MACs if
+    /// the entry uses them. The password provider is only asked if
+    /// `ask_provider` is set.
+    fn entry_decryption(
+        &mut self,
+        header: &FileHeader,
+        ask_provider: bool,
+    ) -> io::Result<Option<(CbcDecryptor, Option<[u8; KEY_SIZE]>)>> {
+        let Some(encryption) = header.encryption()? else {
+            return Ok(None);
+        };
+        let cached = matches!(&self.file_keys, Some((salt, kdf_count, _))
+            if *salt == encryption.salt && *kdf_count == encryption.kdf_count);
+        if !cached {
+            let target = PasswordTarget::Entry(&header.file_name);
+            let keys = self.obtain_keys(target, ask_provider, |password| {
+                let keys = Rar5Keys::derive(password, &encryption.salt, encryption.kdf_count)?;
+                encryption.check_password(&keys, &header.file_name)?;
+                Ok(keys)
+            })?;
+            self.file_keys = Some((encryption.salt, encryption.kdf_count, keys));
+        }
+        let (_, _, keys) = self.file_keys.as_ref().expect("keys were just derived");
+        debug!("[entry_decryption] Decrypting '{}'", header.file_name);
+        Ok(Some((
+            CbcDecryptor::new(&keys.key, &encryption.iv),
+            encryption.use_mac.then_some(keys.hash_key),
+        )))
+    }
+
+    /// Returns how much of the current data area has not been read yet.
+    pub(crate) fn remaining_data(&self) -> u64 {
+        self.current_block_data_size
+    }
+
+    /// Reads the next bytes of the current data area.
+    pub(crate) fn read_data(&mut self, buffer: &mut [u8]) -> io::Result<()> {
+        self.reader.read_exact(buffer)?;
+        self.current_block_data_size -= buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Keeps the decoder state of a finished solid entry for the next one.
+    pub(crate) fn restore_solid_decompressor(&mut self, decompressor: Box<dyn Decompressor>) {
+        self.solid_decompressor = Some(decompressor);
+    }
+
+    /// Reads on into the next volume up to the header continuing the data of
+    /// `file_name`, leaving the reader at that part's data.
+    pub(crate) fn next_data_part(&mut self, file_name: &str) -> io::Result<FileHeader> {
+        loop {
+            let header = match self.read_block() {
+                Some(Ok(BlockHeader::VolumeEnd(_))) => {
+                    self.open_next_volume()?;
+                    continue;
+                }
+                Some(Ok(BlockHeader::File(header))) => header,
+                Some(Ok(BlockHeader::Service(header))) => header.file_header,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!("Archive ended before the rest of '{}'", file_name),
+                    ))
+                }
+            };
+            if !header.base.general_header.header_flags.is_split_before() || header.file_name != file_name {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Expected the rest of '{}', but found '{}'", file_name, header.file_name),
+                ));
+            }
+            return Ok(header);
+        }
+    }
+
+    /// Switches the reader to the next volume, after its signature.
+    fn open_next_volume(&mut self) -> io::Result<()> {
+        let Some(volumes) = self.volumes.as_mut() else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "The archive continues in the next volume, but no volume source is set",
+            ));
+        };
+        self.volume_index += 1;
+        debug!("[open_next_volume] Opening volume {}", self.volume_index);
+        let mut reader = volumes.open_volume(self.volume_index)?;
+        if read_signature(&mut reader)? != RarFormat::Rar5 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Volume {} is not a RAR5 archive", self.volume_index),
+            ));
+        }
+        self.reader = reader;
+        self.headers_encrypted = false;
+        self.current_block_data_size = 0;
+        Ok(())
+    }
+
+    /// Decodes the data of a solid entry the caller skipped, so the shared
+    /// dictionary stays in step for the entries that follow it.
+    ///
+    /// The password provider is not asked for a skipped entry: without cached
+    /// keys or a matching password from `set_password`, the solid stream
+    /// stops here. The failure is kept for the next solid entry the caller
+    /// opens.
+    fn decode_skipped_entry(&mut self, header: &FileHeader) {
+        if header.compression_info.is_solid() && self.solid_error.is_some() {
+            return;
+        }
+        let result = self
+            .entry_decryption(header, false)
+            .and_then(|_| self.open_entry(header, true, false))
+            .and_then(|mut reader| io::copy(&mut reader, &mut io::sink()));
+        if let Err(e) = result {
+            debug!("[decode_skipped_entry] Solid stream broken: {}", e);
+            self.solid_decompressor = None;
+            self.solid_error = Some(io::Error::new(
+                e.kind(),
+                format!("Solid stream broken by the skipped entry '{}': {}", header.file_name, e),
+            ));
         }
     }
 
     fn advance_reader(&mut self) -> io::Result<()> {
+        if let Some(skip_data) = self.data_skipper {
+            self.pending_entry = None;
+            if self.current_block_data_size > 0 {
+                debug!("[advance_reader] Seeking past {} bytes", self.current_block_data_size);
+                skip_data(&mut self.reader, self.current_block_data_size)?;
+                self.current_block_data_size = 0;
+            }
+            return Ok(());
+        }
+        if let Some(header) = self.pending_entry.take() {
+            if self.is_solid && !header.compression_info.is_stored() {
+                self.decode_skipped_entry(&header);
+            }
+        }
         if self.current_block_data_size > 0 {
             debug!("[advance_reader] Skipping {} bytes", self.current_block_data_size);
             let mut limited_reader = self.reader.by_ref().take(self.current_block_data_size);
@@ -49,58 +377,217 @@ impl<R: Read> Archive<R> {
         }
         Ok(())
     }
+
+    /// Reads the next block header, decrypting it once the archive's
+    /// encryption header has been seen.
+    fn read_block_header(&mut self) -> io::Result<(GeneralBlockHeader, Cursor<Vec<u8>>)> {
+        match &self.header_keys {
+            Some((_, _, keys)) if self.headers_encrypted => {
+                let header = read_encrypted_header(&mut self.reader, &keys.key)?;
+                read_checked_block_header(&mut Cursor::new(header))
+            }
+            _ => read_checked_block_header(&mut self.reader),
+        }
+    }
+
+    fn start_header_decryption(&mut self, header: &EncryptionHeader) -> io::Result<()> {
+        let cached = matches!(&self.header_keys, Some((salt, kdf_count, _))
+            if *salt == header.salt && *kdf_count == header.kdf_count);
+        if !cached {
+            let keys = self.obtain_keys(PasswordTarget::Headers, true, |password| header.derive_keys(password))?;
+            self.header_keys = Some((header.salt, header.kdf_count, keys));
+        }
+        self.headers_encrypted = true;
+        Ok(())
+    }
+
+    /// Derives keys for `target`, trying the password from `set_password`
+    /// first and then, if `ask_provider` is set, asking the provider until
+    /// `derive` accepts one. `derive` rejects a wrong password with a
+    /// `PermissionDenied` error.
+    fn obtain_keys<F>(&mut self, target: PasswordTarget, ask_provider: bool, derive: F) -> io::Result<Rar5Keys>
+    where
+        F: Fn(&[u8]) -> io::Result<Rar5Keys>,
+    {
+        let mut failed_attempts = 0;
+        if let Some(password) = &self.password {
+            match derive(password.as_bytes()) {
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => failed_attempts += 1,
+                result => return result,
+            }
+        }
+
+        loop {
+            let Some(provider) = self.password_provider.as_mut().filter(|_| ask_provider) else {
+                return Err(match failed_attempts {
+                    0 => password_required(&target.to_string()),
+                    _ => incorrect_password(&target.to_string()),
+                });
+            };
+            if failed_attempts >= self.max_password_attempts {
+                return Err(incorrect_password(&target.to_string()));
+            }
+            debug!("[obtain_keys] Asking for a password for {} after {} failures", target, failed_attempts);
+            let password = match provider.password(&PasswordRequest { target, failed_attempts }) {
+                PasswordResponse::Password(password) => password,
+                PasswordResponse::Cancel => return Err(PasswordCancelled { target: target.to_string() }.into()),
+            };
+            match derive(password.as_bytes()) {
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => failed_attempts += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> Archive<R> {
+    /// Makes the iterator seek past data areas instead of reading them. Skipped
+    /// solid entries are no longer decoded, so their data must be read in
+    /// order through `read_file_data_at`.
+    pub(crate) fn seek_past_data(&mut self) {
+        self.data_skipper = Some(|reader, size| {
+            let offset = i64::try_from(size)
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Data area is too large to seek past"))?;
+            reader.seek(SeekFrom::Current(offset)).map(|_| ())
+        });
+    }
+
+    pub(crate) fn stream_position(&mut self) -> io::Result<u64> {
+        self.reader.stream_position()
+    }
+
+    /// Reads an entry's data from `data_offset`, as `read_file_data` would
+    /// right after the iterator returned its header.
+    pub(crate) fn read_file_data_at(&mut self, header: &FileHeader, data_offset: u64) -> io::Result<Vec<u8>> {
+        self.reader.seek(SeekFrom::Start(data_offset))?;
+        self.current_block_data_size = header.base.general_header.data_size.unwrap_or(0);
+        self.read_file_data(header)
+    }
+}
+
+pub(crate) fn decompression_error(error: DecompressionError) -> io::Error {
+    io::Error::other(format!("Decompression error: {:?}", error))
 }
 
 impl<R: Read> Iterator for Archive<R> {
     type Item = io::Result<BlockHeader>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        debug!("[next] Advancing reader...");
+        loop {
+            let block = self.read_block()?;
+            if self.volumes.is_none() {
+                return Some(block);
+            }
+            // Hide the seams between volumes: the headers every volume repeats
+            // and the parts of entries continued from the previous volume.
+            match block {
+                Ok(BlockHeader::VolumeEnd(_)) => {
+                    if let Err(e) = self.open_next_volume() {
+                        return Some(Err(e));
+                    }
+                }
+                Ok(BlockHeader::Main(_) | BlockHeader::Encryption(_)) if self.volume_index > 0 => {}
+                Ok(BlockHeader::File(header)) if header.base.general_header.header_flags.is_split_before() => {
+                    debug!("[next] Skipping the rest of '{}'", header.file_name);
+                }
+                Ok(BlockHeader::Service(header))
+                    if header.file_header.base.general_header.header_flags.is_split_before() => {}
+                block => return Some(block),
+            }
+        }
+    }
+}
+
+impl<R: Read> Archive<R> {
+    /// Reads and decodes the next block of the current volume.
+    fn read_block(&mut self) -> Option<io::Result<BlockHeader>> {
+        debug!("[read_block] Advancing reader...");
         if let Err(e) = self.advance_reader() {
             return Some(Err(e));
         }
 
-        debug!("[next] Reading general block header...");
-        let general_header = match read_general_block_header(&mut self.reader) {
-            Ok(header) => {
-                debug!("[next] Read general block header: {:?}", header);
-                header
+        debug!("[read_block] Reading general block header...");
+        let (general_header, mut header_reader) = match self.read_block_header() {
+            Ok((header, header_reader)) => {
+                debug!("[read_block] Read general block header: {:?}", header);
+                (header, header_reader)
             }
             Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                debug!("[next] EOF reached.");
+                debug!("[read_block] EOF reached.");
                 return None;
             }
             Err(e) => {
-                debug!("[next] Error reading general block header: {}", e);
+                debug!("[read_block] Error reading general block header: {}", e);
                 return Some(Err(e));
             }
         };
 
+        if general_header.has_crc_mismatch() && self.crc_mode == CrcMode::Strict {
+            return Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Header CRC32 mismatch: stored {:#010x}, computed {:#010x}",
+                    general_header.crc32,
+                    general_header.computed_crc32.unwrap_or_default()
+                ),
+            )));
+        }
+
         if let Some(data_size) = general_header.data_size {
-            debug!("[next] Setting current_block_data_size to {}", data_size);
+            debug!("[read_block] Setting current_block_data_size to {}", data_size);
             self.current_block_data_size = data_size;
         }
 
         let block_header_result = match general_header.header_type {
-            1 => MainArchiveHeader::decode(general_header, &mut self.reader)
-                .map(BlockHeader::Main),
-            2 => FileHeader::decode(general_header, &mut self.reader)
-                .map(BlockHeader::File),
-            5 => EndOfArchiveHeader::decode(general_header, &mut self.reader)
-                .map(BlockHeader::End),
-            _ => Err(io::Error::new(
+            1 => MainArchiveHeader::decode(general_header, &mut header_reader).and_then(|header| {
+                let volume_number = header.volume_number.unwrap_or(0);
+                if self.volumes.is_some() && volume_number != self.volume_index {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Found volume number {} where volume {} was expected", volume_number, self.volume_index),
+                    ));
+                }
+                self.is_solid = header.is_solid();
+                Ok(BlockHeader::Main(header))
+            }),
+            2 => FileHeader::decode(general_header, &mut header_reader).map(|header| {
+                // Continued parts are read along with their first part.
+                if self.current_block_data_size > 0 && !header.base.general_header.header_flags.is_split_before() {
+                    self.pending_entry = Some(header.clone());
+                }
+                BlockHeader::File(header)
+            }),
+            3 => ServiceHeader::decode(general_header, &mut header_reader).map(BlockHeader::Service),
+            4 => EncryptionHeader::decode(general_header, &mut header_reader).and_then(|header| {
+                self.start_header_decryption(&header)?;
+                Ok(BlockHeader::Encryption(header))
+            }),
+            5 => EndOfArchiveHeader::decode(general_header, &mut header_reader).map(|header| {
+                if header.is_last_volume() {
+                    BlockHeader::End(header)
+                } else {
+                    debug!("[read_block] Archive continues in the next volume");
+                    BlockHeader::VolumeEnd(header)
+                }
+            }),
+            _ if general_header.header_flags.skip_if_unknown() => {
+                debug!("[read_block] Skipping unknown block type {}", general_header.header_type);
+                let mut raw = Vec::new();
+                header_reader.read_to_end(&mut raw).map(|_| BlockHeader::Unknown { general_header, raw })
+            }
+            header_type => Err(io::Error::new(
                 io::ErrorKind::InvalidData,
-                "Unknown block type",
+                format!("Unknown block type {}", header_type),
             )),
         };
 
         match block_header_result {
             Ok(block_header) => {
-                debug!("[next] Successfully decoded block header.");
+                debug!("[read_block] Successfully decoded block header.");
                 Some(Ok(block_header))
             }
             Err(e) => {
-                debug!("[next] Error decoding block header: {}", e);
+                debug!("[read_block] Error decoding block header: {}", e);
                 Some(Err(e))
             }
         }
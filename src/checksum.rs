@@ -0,0 +1,11 @@
+// src/checksum.rs
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+
+/// The CRC32 variant RAR uses for header and data checksums.
+pub static CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Computes the CRC32 of a byte slice.
+pub fn crc32(data: &[u8]) -> u32 {
+    CRC32.checksum(data)
+}
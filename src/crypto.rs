@@ -0,0 +1,222 @@
+// src/crypto.rs
+
+use crate::decoder::{read_vint, MAX_HEADER_SIZE};
+use aes::cipher::{BlockDecryptMut, KeyIvInit};
+use aes::Aes256;
+use hmac::{Hmac, Mac};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::io::{self, Cursor, Read};
+use zeroize::Zeroize;
+
+pub const KEY_SIZE: usize = 32;
+pub const SALT_SIZE: usize = 16;
+pub const IV_SIZE: usize = 16;
+/// The AES block size; encrypted headers and data are padded to it.
+pub const BLOCK_SIZE: usize = 16;
+/// The largest log2 PBKDF2 iteration count RAR accepts.
+pub const MAX_KDF_COUNT: u8 = 24;
+/// Password check value followed by the first bytes of its SHA-256.
+pub const CHECK_VALUE_SIZE: usize = 12;
+const PASSWORD_CHECK_SIZE: usize = 8;
+
+/// The values RAR5 derives from a password and salt.
+pub struct Rar5Keys {
+    /// The AES-256 key.
+    pub key: [u8; KEY_SIZE],
+    /// The key that turns data checksums into MACs for encrypted entries.
+    pub hash_key: [u8; KEY_SIZE],
+    /// The value stored in headers to detect a wrong password.
+    pub password_check: [u8; PASSWORD_CHECK_SIZE],
+}
+
+impl Rar5Keys {
+    /// Runs PBKDF2-HMAC-SHA256 with `2^kdf_count` iterations for the key, then
+    /// continues for 16 more iterations for the hash key and 16 more for the
+    /// password check value.
+    pub fn derive(password: &[u8], salt: &[u8; SALT_SIZE], kdf_count: u8) -> io::Result<Self> {
+        if kdf_count > MAX_KDF_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("KDF iteration count 2^{} is too large", kdf_count),
+            ));
+        }
+        debug!("[Rar5Keys::derive] Deriving keys with 2^{} iterations", kdf_count);
+        let mac = Hmac::<Sha256>::new_from_slice(password).expect("HMAC accepts any key length");
+
+        let mut block = mac.clone();
+        block.update(salt);
+        block.update(&1u32.to_be_bytes());
+        let mut u: [u8; KEY_SIZE] = block.finalize().into_bytes().into();
+        let mut accumulated = u;
+
+        let mut outputs = [[0u8; KEY_SIZE]; 3];
+        let iterations = [(1u32 << kdf_count) - 1, 16, 16];
+        for (output, count) in outputs.iter_mut().zip(iterations) {
+            for _ in 0..count {
+                let mut next = mac.clone();
+                next.update(&u);
+                u = next.finalize().into_bytes().into();
+                for (a, b) in accumulated.iter_mut().zip(u) {
+                    *a ^= b;
+                }
+            }
+            *output = accumulated;
+        }
+
+        let mut password_check = [0u8; PASSWORD_CHECK_SIZE];
+        for (index, byte) in outputs[2].iter().enumerate() {
+            password_check[index % PASSWORD_CHECK_SIZE] ^= byte;
+        }
+        let keys = Rar5Keys {
+            key: outputs[0],
+            hash_key: outputs[1],
+            password_check,
+        };
+        u.zeroize();
+        accumulated.zeroize();
+        outputs.zeroize();
+        password_check.zeroize();
+        Ok(keys)
+    }
+
+    /// Compares the derived password check with a stored check value. Returns
+    /// true if the value is damaged and cannot be used for the comparison.
+    pub fn matches_check_value(&self, check_value: &[u8; CHECK_VALUE_SIZE]) -> bool {
+        let (stored_check, checksum) = check_value.split_at(PASSWORD_CHECK_SIZE);
+        if Sha256::digest(stored_check)[..checksum.len()] != *checksum {
+            debug!("[Rar5Keys::matches_check_value] Check value is damaged; skipping the check");
+            return true;
+        }
+        stored_check == self.password_check
+    }
+}
+
+impl Drop for Rar5Keys {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.hash_key.zeroize();
+        self.password_check.zeroize();
+    }
+}
+
+/// Converts a data CRC32 to the MAC form stored for encrypted entries.
+pub fn crc32_to_mac(hash_key: &[u8; KEY_SIZE], crc32: u32) -> u32 {
+    let digest = hmac_sha256(hash_key, &crc32.to_le_bytes());
+    digest
+        .iter()
+        .enumerate()
+        .fold(0, |mac, (index, &byte)| mac ^ (byte as u32) << ((index & 3) * 8))
+}
+
+/// Converts a BLAKE2sp digest to the MAC form stored for encrypted entries.
+pub fn digest_to_mac(hash_key: &[u8; KEY_SIZE], digest: &[u8; KEY_SIZE]) -> [u8; KEY_SIZE] {
+    hmac_sha256(hash_key, digest)
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; KEY_SIZE] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// AES-256-CBC decryption that keeps its chaining state between calls.
+pub struct CbcDecryptor {
+    cipher: cbc::Decryptor<Aes256>,
+}
+
+impl CbcDecryptor {
+    pub fn new(key: &[u8; KEY_SIZE], iv: &[u8; IV_SIZE]) -> Self {
+        CbcDecryptor {
+            cipher: cbc::Decryptor::new(key.into(), iv.into()),
+        }
+    }
+
+    /// Decrypts whole blocks in place; a trailing partial block is left as-is.
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        for block in data.chunks_exact_mut(BLOCK_SIZE) {
+            self.cipher.decrypt_block_mut(block.into());
+        }
+    }
+}
+
+/// Reads one encrypted block header: a 16-byte IV followed by the header,
+/// padded to the AES block size. Returns the plaintext header, starting at
+/// its CRC32 and without the padding.
+pub fn read_encrypted_header<R: Read + ?Sized>(reader: &mut R, key: &[u8; KEY_SIZE]) -> io::Result<Vec<u8>> {
+    let mut iv = [0u8; IV_SIZE];
+    reader.read_exact(&mut iv)?;
+    let mut decryptor = CbcDecryptor::new(key, &iv);
+
+    let mut header = vec![0u8; BLOCK_SIZE];
+    reader.read_exact(&mut header)?;
+    decryptor.decrypt(&mut header);
+
+    let mut size_reader = Cursor::new(&header[4..]);
+    let header_size = read_vint(&mut size_reader)?;
+    if header_size > MAX_HEADER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Encrypted header size too large; the password may be wrong",
+        ));
+    }
+    let total_size = 4 + size_reader.position() as usize + header_size as usize;
+    let padded_size = total_size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+
+    let mut rest = vec![0u8; padded_size - BLOCK_SIZE];
+    reader.read_exact(&mut rest)?;
+    decryptor.decrypt(&mut rest);
+    header.extend_from_slice(&rest);
+    header.truncate(total_size);
+    Ok(header)
+}
+
+/// The error returned when a password is needed but none was given.
+pub fn password_required(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, format!("A password is required for {}", what))
+}
+
+/// The error returned when the password check value does not match.
+pub fn incorrect_password(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, format!("Incorrect password for {}", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn derive_matches_reference_values() {
+        let keys = Rar5Keys::derive(b"secret", &[1; SALT_SIZE], 4).unwrap();
+        assert_eq!(hex(&keys.key), "a37dd91abc5a1134e5eae866a35ec0a34c807251269881ebbe4ac81145af17a4");
+        assert_eq!(hex(&keys.hash_key), "19e6ef85a374ac0e0a2c40243c27c9e421611ffc71b0e27824211e04af2116f8");
+        assert_eq!(hex(&keys.password_check), "2ea8b6a0a13fc4ff");
+    }
+
+    #[test]
+    fn derive_rejects_excessive_iteration_counts() {
+        assert!(Rar5Keys::derive(b"secret", &[1; SALT_SIZE], MAX_KDF_COUNT + 1).is_err());
+    }
+
+    #[test]
+    fn check_value_detects_wrong_password() {
+        let keys = Rar5Keys::derive(b"secret", &[1; SALT_SIZE], 4).unwrap();
+        let check_value_for = |check: [u8; PASSWORD_CHECK_SIZE]| {
+            let mut check_value = [0u8; CHECK_VALUE_SIZE];
+            check_value[..8].copy_from_slice(&check);
+            check_value[8..].copy_from_slice(&Sha256::digest(check)[..4]);
+            check_value
+        };
+        assert!(keys.matches_check_value(&check_value_for(keys.password_check)));
+        assert!(!keys.matches_check_value(&check_value_for([0; PASSWORD_CHECK_SIZE])));
+
+        // A check value with a bad checksum is damaged and cannot reject the password.
+        let mut damaged = check_value_for([0; PASSWORD_CHECK_SIZE]);
+        damaged[8] ^= 1;
+        assert!(keys.matches_check_value(&damaged));
+    }
+}
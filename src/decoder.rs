@@ -1,7 +1,9 @@
 // src/decoder.rs
 
-use crate::structures::general_block_header::GeneralBlockHeader;
-use std::io::{self, Read};
+use crate::checksum::crc32;
+use crate::signature::{detect_format, RarFormat, RAR4_SIGNATURE};
+use crate::structures::general_block_header::{GeneralBlockHeader, HeaderFlags};
+use std::io::{self, Cursor, Read};
 use log::debug;
 
 /// Reads a variable-length integer (vint) from a Read source.
@@ -40,6 +42,77 @@ pub fn read_vint<R: Read + ?Sized>(reader: &mut R) -> Result<u64, io::Error> {
     Ok(value)
 }
 
+/// Reads the archive signature and returns the format it identifies.
+///
+/// Only the bytes of the signature are consumed: seven for RAR 1.5-4.x and
+/// eight for RAR 5.0.
+pub fn read_signature<R: Read + ?Sized>(reader: &mut R) -> Result<RarFormat, io::Error> {
+    let mut signature = [0u8; 8];
+    let mut length = RAR4_SIGNATURE.len();
+    let mut result = reader.read_exact(&mut signature[..length]);
+    if result.is_ok() && signature[6] == 0x01 {
+        length += 1;
+        result = reader.read_exact(&mut signature[7..length]);
+    }
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(unrecognised_signature()),
+        other => other?,
+    }
+    debug!("[read_signature] Signature: {:02x?}", &signature[..length]);
+
+    detect_format(&signature[..length]).ok_or_else(unrecognised_signature)
+}
+
+fn unrecognised_signature() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "Not a RAR archive: unrecognised signature")
+}
+
+/// RAR 5.0 limits block headers to 2 MB.
+pub(crate) const MAX_HEADER_SIZE: u64 = 2 * 1024 * 1024;
+
+/// A reader that keeps a copy of every byte read through it.
+struct RecordingReader<'a, R: Read + ?Sized> {
+    inner: &'a mut R,
+    recorded: Vec<u8>,
+}
+
+impl<R: Read + ?Sized> Read for RecordingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.recorded.extend_from_slice(&buf[..count]);
+        Ok(count)
+    }
+}
+
+/// Reads a whole block header into memory and checks its CRC32.
+///
+/// The CRC32 covers everything from the header size field to the end of the
+/// extra area. The computed value is stored in `computed_crc32`; the returned
+/// cursor is positioned at the type-specific fields of the header.
+pub fn read_checked_block_header<R: Read + ?Sized>(
+    reader: &mut R,
+) -> Result<(GeneralBlockHeader, Cursor<Vec<u8>>), io::Error> {
+    let mut recorder = RecordingReader {
+        inner: reader,
+        recorded: Vec::new(),
+    };
+    let mut crc_bytes = [0u8; 4];
+    recorder.read_exact(&mut crc_bytes)?;
+    let header_size = read_vint(&mut recorder)?;
+    if header_size > MAX_HEADER_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Header size too large"));
+    }
+    recorder.read_exact(&mut vec![0u8; header_size as usize])?;
+    let header_bytes = recorder.recorded;
+
+    let computed_crc32 = crc32(&header_bytes[4..]);
+    debug!("[read_checked_block_header] Computed CRC32: {:#010x}", computed_crc32);
+    let mut header_reader = Cursor::new(header_bytes);
+    let mut general_header = read_general_block_header(&mut header_reader)?;
+    general_header.computed_crc32 = Some(computed_crc32);
+    Ok((general_header, header_reader))
+}
+
 /// Reads and parses a GeneralBlockHeader from a Read source.
 pub fn read_general_block_header<R: Read + ?Sized>(
     reader: &mut R,
@@ -61,10 +134,18 @@ pub fn read_general_block_header<R: Read + ?Sized>(
     debug!("[read_general_block_header] Header type: {}", header_type);
 
     debug!("[read_general_block_header] Reading header flags...");
-    let header_flags = read_vint(reader)?;
-    debug!("[read_general_block_header] Header flags: {:#06x}", header_flags);
+    let header_flags = HeaderFlags(read_vint(reader)?);
+    debug!("[read_general_block_header] Header flags: {:#06x}", header_flags.0);
+
+    let extra_area_size = if header_flags.has_extra_area() {
+        debug!("[read_general_block_header] Reading extra area size...");
+        Some(read_vint(reader)?)
+    } else {
+        None
+    };
+    debug!("[read_general_block_header] Extra area size: {:?}", extra_area_size);
 
-    let data_size = if (header_flags & 0x0001) != 0 {
+    let data_size = if header_flags.has_data_area() {
         debug!("[read_general_block_header] Reading data size...");
         Some(read_vint(reader)?)
     } else {
@@ -81,6 +162,8 @@ pub fn read_general_block_header<R: Read + ?Sized>(
         header_size,
         header_type,
         header_flags,
+        extra_area_size,
         data_size,
+        computed_crc32: None,
     })
 }
@@ -0,0 +1,103 @@
+// src/decompression/bit_reader.rs
+
+/// Reads an in-memory byte slice as a stream of bits, most significant bit first.
+///
+/// Reads past the end of the slice yield zero bits; callers are expected to check
+/// `bit_position` against the bounds of the block they are decoding.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    bit_position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            bit_position: 0,
+        }
+    }
+
+    pub fn bit_position(&self) -> usize {
+        self.bit_position
+    }
+
+    pub fn set_bit_position(&mut self, bit_position: usize) {
+        self.bit_position = bit_position;
+    }
+
+    pub fn byte_position(&self) -> usize {
+        self.bit_position / 8
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the next 32 bits without consuming them.
+    pub fn peek32(&self) -> u32 {
+        let start = self.byte_position();
+        let mut value: u64 = 0;
+        for offset in 0..5 {
+            value = (value << 8) | *self.data.get(start + offset).unwrap_or(&0) as u64;
+        }
+        (value >> (8 - self.bit_position % 8)) as u32
+    }
+
+    /// Returns the next 16 bits without consuming them.
+    pub fn peek16(&self) -> u32 {
+        self.peek32() >> 16
+    }
+
+    pub fn skip(&mut self, count: u32) {
+        self.bit_position += count as usize;
+    }
+
+    pub fn align_to_byte(&mut self) {
+        self.bit_position = self.bit_position.div_ceil(8) * 8;
+    }
+
+    /// Consumes and returns the next `count` bits (up to 64).
+    pub fn read_bits(&mut self, count: u32) -> u64 {
+        let mut value: u64 = 0;
+        let mut remaining = count;
+        while remaining > 0 {
+            let chunk = remaining.min(32);
+            value = (value << chunk) | (self.peek32() >> (32 - chunk)) as u64;
+            self.skip(chunk);
+            remaining -= chunk;
+        }
+        value
+    }
+
+    pub fn read_byte(&mut self) -> u8 {
+        self.read_bits(8) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bits_across_byte_boundaries() {
+        let data = [0b1011_0011, 0b0101_1100];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.read_bits(3), 0b101);
+        assert_eq!(reader.read_bits(7), 0b100_1101);
+        assert_eq!(reader.read_bits(6), 0b01_1100);
+        assert_eq!(reader.read_bits(8), 0);
+    }
+
+    #[test]
+    fn align_to_byte_skips_partial_byte() {
+        let data = [0xFF, 0xAB];
+        let mut reader = BitReader::new(&data);
+        reader.skip(1);
+        reader.align_to_byte();
+        assert_eq!(reader.read_byte(), 0xAB);
+    }
+}
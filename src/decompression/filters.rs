@@ -0,0 +1,191 @@
+// src/decompression/filters.rs
+
+use crate::decompression::bit_reader::BitReader;
+use crate::decompression::DecompressionError;
+
+/// Filters longer than this are ignored, as RAR does.
+pub(crate) const MAX_FILTER_BLOCK_SIZE: usize = 0x400000;
+
+/// The address space E8/E9 call targets are translated within.
+const E8_FILE_SIZE: u32 = 0x1000000;
+
+/// A post-processing transform applied to a block of decoded LZ output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    /// Byte-wise delta coding over interleaved channels (multimedia data).
+    Delta { channels: usize },
+    /// x86 CALL (0xE8) relative address translation.
+    E8,
+    /// x86 CALL (0xE8) and JMP (0xE9) relative address translation.
+    E8E9,
+    /// ARM BL instruction address translation.
+    Arm,
+}
+
+/// A filter queued against a range of the decoded stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    /// Absolute stream position of the first filtered byte.
+    pub block_start: u64,
+    pub block_length: usize,
+}
+
+impl Filter {
+    /// Reads a filter definition from the LZ stream. `position` is the number of
+    /// bytes decoded so far; the stored block start is relative to it.
+    pub fn read(reader: &mut BitReader, position: u64) -> Result<Self, DecompressionError> {
+        let block_start = read_filter_data(reader) as u64;
+        let mut block_length = read_filter_data(reader) as usize;
+        if block_length > MAX_FILTER_BLOCK_SIZE {
+            block_length = 0;
+        }
+        let kind = match reader.read_bits(3) {
+            0 => FilterKind::Delta {
+                channels: reader.read_bits(5) as usize + 1,
+            },
+            1 => FilterKind::E8,
+            2 => FilterKind::E8E9,
+            3 => FilterKind::Arm,
+            _ => return Err(DecompressionError::InvalidFilter),
+        };
+        Ok(Filter {
+            kind,
+            block_start: position + block_start,
+            block_length,
+        })
+    }
+
+    pub fn block_end(&self) -> u64 {
+        self.block_start + self.block_length as u64
+    }
+}
+
+fn read_filter_data(reader: &mut BitReader) -> u32 {
+    let byte_count = reader.read_bits(2) as u32 + 1;
+    (0..byte_count).fold(0, |value, index| value | (reader.read_byte() as u32) << (index * 8))
+}
+
+impl FilterKind {
+    /// Reverses the filter on `data`. `file_offset` is the position of the
+    /// block's first byte within the file being extracted.
+    pub fn apply(&self, data: &mut Vec<u8>, file_offset: u64) {
+        match *self {
+            FilterKind::Delta { channels } => *data = undo_delta(data, channels),
+            FilterKind::E8 => undo_e8(data, file_offset as u32, false),
+            FilterKind::E8E9 => undo_e8(data, file_offset as u32, true),
+            FilterKind::Arm => undo_arm(data, file_offset as u32),
+        }
+    }
+}
+
+/// Bytes of each channel are stored as one contiguous run of deltas; rebuild
+/// them and put them back at their interleaved positions.
+fn undo_delta(data: &[u8], channels: usize) -> Vec<u8> {
+    let mut output = vec![0u8; data.len()];
+    let mut source = data.iter();
+    for channel in 0..channels {
+        let mut previous: u8 = 0;
+        for destination in (channel..data.len()).step_by(channels) {
+            previous = previous.wrapping_sub(*source.next().unwrap_or(&0));
+            output[destination] = previous;
+        }
+    }
+    output
+}
+
+fn undo_e8(data: &mut [u8], file_offset: u32, include_e9: bool) {
+    let mut position = 0;
+    while position + 4 < data.len() {
+        let opcode = data[position];
+        position += 1;
+        if opcode != 0xE8 && !(include_e9 && opcode == 0xE9) {
+            continue;
+        }
+
+        let offset = (position as u32).wrapping_add(file_offset) % E8_FILE_SIZE;
+        let operand: &mut [u8; 4] = (&mut data[position..position + 4]).try_into().unwrap();
+        let address = u32::from_le_bytes(*operand);
+        if address & 0x8000_0000 != 0 {
+            if address.wrapping_add(offset) & 0x8000_0000 == 0 {
+                *operand = address.wrapping_add(E8_FILE_SIZE).to_le_bytes();
+            }
+        } else if address.wrapping_sub(E8_FILE_SIZE) & 0x8000_0000 != 0 {
+            *operand = address.wrapping_sub(offset).to_le_bytes();
+        }
+        position += 4;
+    }
+}
+
+fn undo_arm(data: &mut [u8], file_offset: u32) {
+    let mut position = 0;
+    while position + 3 < data.len() {
+        let instruction = &mut data[position..position + 4];
+        // A BL instruction with the "always" condition.
+        if instruction[3] == 0xEB {
+            let target = u32::from_le_bytes([instruction[0], instruction[1], instruction[2], 0]);
+            let offset = target.wrapping_sub(file_offset.wrapping_add(position as u32) / 4);
+            instruction[..3].copy_from_slice(&offset.to_le_bytes()[..3]);
+        }
+        position += 4;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_filter_restores_interleaved_channels() {
+        // Two channels: [10, 20, 30] and [1, 2, 3], each stored as negated deltas.
+        let mut data = vec![246, 246, 246, 255, 255, 255];
+        FilterKind::Delta { channels: 2 }.apply(&mut data, 0);
+        assert_eq!(data, vec![10, 1, 20, 2, 30, 3]);
+    }
+
+    #[test]
+    fn e8_filter_converts_absolute_calls_to_relative() {
+        let mut data = vec![0x90, 0xE8, 0x00, 0x10, 0x00, 0x00, 0xE9, 0x00, 0x10, 0x00, 0x00, 0x90];
+        FilterKind::E8.apply(&mut data, 0x100);
+        // The operand at offset 2 in a block at file offset 0x100 is 0x1000 - 0x102.
+        assert_eq!(&data[2..6], &0x0EFEu32.to_le_bytes());
+        // 0xE9 is left alone by the plain E8 filter.
+        assert_eq!(&data[7..11], &0x1000u32.to_le_bytes());
+    }
+
+    #[test]
+    fn e8_filter_wraps_negative_addresses_into_file_space() {
+        let mut data = vec![0xE8, 0xFF, 0xFF, 0xFF, 0xFF, 0x90];
+        FilterKind::E8.apply(&mut data, 0);
+        assert_eq!(&data[1..5], &0x00FF_FFFFu32.to_le_bytes());
+    }
+
+    #[test]
+    fn e8e9_filter_converts_calls_and_jumps() {
+        let mut data = vec![0xE8, 0x00, 0x10, 0x00, 0x00, 0xE9, 0x00, 0x10, 0x00, 0x00, 0x90];
+        FilterKind::E8E9.apply(&mut data, 0);
+        assert_eq!(&data[1..5], &0x0FFFu32.to_le_bytes());
+        assert_eq!(&data[6..10], &0x0FFAu32.to_le_bytes());
+    }
+
+    #[test]
+    fn arm_filter_converts_bl_targets() {
+        let mut data = vec![0x00, 0x01, 0x00, 0xEB, 0x00, 0x01, 0x00, 0xEA];
+        FilterKind::Arm.apply(&mut data, 0x40);
+        // (0x40 + 0) / 4 = 0x10 is subtracted from the BL target.
+        assert_eq!(data[..4], [0xF0, 0x00, 0x00, 0xEB]);
+        // Other instructions are untouched.
+        assert_eq!(data[4..], [0x00, 0x01, 0x00, 0xEA]);
+    }
+
+    #[test]
+    fn read_parses_filter_definition() {
+        // Start: 1 byte (0x10), length: 1 byte (0x20), type 0 (delta), 3 channels.
+        let data = [0x04, 0x02, 0x00, 0x20];
+        let mut reader = BitReader::new(&data);
+        let filter = Filter::read(&mut reader, 100).unwrap();
+        assert_eq!(filter.kind, FilterKind::Delta { channels: 3 });
+        assert_eq!(filter.block_start, 116);
+        assert_eq!(filter.block_length, 32);
+    }
+}
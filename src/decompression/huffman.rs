@@ -0,0 +1,113 @@
+// src/decompression/huffman.rs
+
+use crate::decompression::bit_reader::BitReader;
+
+const MAX_CODE_LENGTH: usize = 15;
+const QUICK_BITS: u32 = 10;
+
+/// A canonical Huffman decoding table built from per-symbol code lengths.
+///
+/// Codes are assigned in order of increasing length and, within one length, in
+/// order of increasing symbol value, as RAR does.
+pub struct HuffmanTable {
+    decode_len: [u32; MAX_CODE_LENGTH + 1],
+    decode_pos: [u32; MAX_CODE_LENGTH + 1],
+    symbols: Vec<u16>,
+    quick: Vec<(u16, u8)>,
+}
+
+impl HuffmanTable {
+    pub fn new(lengths: &[u8]) -> Self {
+        let mut length_count = [0u32; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            length_count[(length & 0x0F) as usize] += 1;
+        }
+        length_count[0] = 0;
+
+        let mut decode_len = [0u32; MAX_CODE_LENGTH + 1];
+        let mut decode_pos = [0u32; MAX_CODE_LENGTH + 1];
+        let mut upper_limit: u32 = 0;
+        for bits in 1..=MAX_CODE_LENGTH {
+            upper_limit += length_count[bits];
+            decode_len[bits] = upper_limit << (16 - bits);
+            upper_limit *= 2;
+            decode_pos[bits] = decode_pos[bits - 1] + length_count[bits - 1];
+        }
+
+        let mut next_pos = decode_pos;
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            let length = (length & 0x0F) as usize;
+            if length != 0 {
+                symbols[next_pos[length] as usize] = symbol as u16;
+                next_pos[length] += 1;
+            }
+        }
+
+        let mut table = HuffmanTable {
+            decode_len,
+            decode_pos,
+            symbols,
+            quick: Vec::new(),
+        };
+        table.quick = (0..1u32 << QUICK_BITS)
+            .map(|prefix| {
+                let bit_field = prefix << (16 - QUICK_BITS);
+                let bits = table.code_length(bit_field);
+                if bits <= QUICK_BITS {
+                    (table.symbol_at(bit_field, bits), bits as u8)
+                } else {
+                    (0, 0)
+                }
+            })
+            .collect();
+        table
+    }
+
+    /// Decodes the next symbol from the reader.
+    pub fn decode(&self, reader: &mut BitReader) -> u16 {
+        let bit_field = reader.peek16() & 0xFFFE;
+        if bit_field < self.decode_len[QUICK_BITS as usize] {
+            let (symbol, bits) = self.quick[(bit_field >> (16 - QUICK_BITS)) as usize];
+            reader.skip(bits as u32);
+            return symbol;
+        }
+        let bits = self.code_length(bit_field);
+        reader.skip(bits);
+        self.symbol_at(bit_field, bits)
+    }
+
+    fn code_length(&self, bit_field: u32) -> u32 {
+        (1..MAX_CODE_LENGTH)
+            .find(|&bits| bit_field < self.decode_len[bits])
+            .unwrap_or(MAX_CODE_LENGTH) as u32
+    }
+
+    fn symbol_at(&self, bit_field: u32, bits: u32) -> u16 {
+        let distance = (bit_field - self.decode_len[bits as usize - 1]) >> (16 - bits);
+        let position = (self.decode_pos[bits as usize] + distance) as usize;
+        self.symbols
+            .get(position)
+            .or_else(|| self.symbols.first())
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_canonical_codes() {
+        // Lengths 2, 1, 3, 3 give codes: B=0, A=10, C=110, D=111.
+        let table = HuffmanTable::new(&[2, 1, 3, 3]);
+        let data = [0b0101_1011, 0b1000_0000];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(table.decode(&mut reader), 1);
+        assert_eq!(table.decode(&mut reader), 0);
+        assert_eq!(table.decode(&mut reader), 2);
+        assert_eq!(table.decode(&mut reader), 3);
+        assert_eq!(reader.bit_position(), 9);
+    }
+}
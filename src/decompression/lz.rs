@@ -0,0 +1,528 @@
+// src/decompression/lz.rs
+
+use crate::decompression::bit_reader::BitReader;
+use crate::decompression::filters::{Filter, MAX_FILTER_BLOCK_SIZE};
+use crate::decompression::huffman::HuffmanTable;
+use crate::decompression::DecompressionError;
+use log::debug;
+use std::collections::VecDeque;
+
+const BIT_LENGTH_CODES: usize = 20;
+const MAIN_CODES: usize = 306;
+const DISTANCE_CODES: usize = 64;
+const EXTENDED_DISTANCE_CODES: usize = 80;
+const LOW_DISTANCE_CODES: usize = 16;
+const REPEAT_CODES: usize = 44;
+
+/// The longest match a single LZ symbol can produce, including distance bonuses.
+const MAX_MATCH_LENGTH: u64 = 0x1001 + 3;
+
+/// The smallest window, whatever the dictionary size: a filter block of the
+/// largest size, plus the match that completes it and the next one, has to fit
+/// until the block can be filtered. unrar has a minimum window for the same
+/// reason. The window only grows as far as the data needs.
+const MIN_WINDOW_SIZE: usize = MAX_FILTER_BLOCK_SIZE + 2 * MAX_MATCH_LENGTH as usize;
+
+/// Pending filters beyond this count are discarded to bound memory use.
+const MAX_PENDING_FILTERS: usize = 8192;
+
+/// The most input decoding one step can look at: a block header with its
+/// Huffman tables, or a single symbol. Decoding waits until this much of the
+/// current block is buffered.
+const INPUT_LOOKAHEAD: usize = 4096;
+
+/// The longest block header: flags, checksum and three size bytes, plus the
+/// byte alignment in front of it.
+const MAX_BLOCK_HEADER_SIZE: usize = 6;
+
+/// Decoded bytes gathered before they are handed out.
+const OUTPUT_CHUNK: u64 = 64 * 1024;
+
+/// The header in front of every compressed block of a RAR5 LZ stream.
+struct CompressedBlockHeader {
+    /// Byte position just past the block.
+    end_byte: usize,
+    /// Bit position just past the last valid bit of the block.
+    end_bit: usize,
+    last_block_in_file: bool,
+    table_present: bool,
+}
+
+impl CompressedBlockHeader {
+    fn read(reader: &mut BitReader) -> Result<Self, DecompressionError> {
+        reader.align_to_byte();
+        let flags = reader.read_byte();
+        let checksum = reader.read_byte();
+        let byte_count = ((flags >> 3) & 3) + 1;
+        if byte_count == 4 {
+            return Err(DecompressionError::InvalidBlockHeader);
+        }
+        let mut block_size: usize = 0;
+        for index in 0..byte_count {
+            block_size |= (reader.read_byte() as usize) << (index * 8);
+        }
+        let expected = 0x5A
+            ^ flags
+            ^ block_size as u8
+            ^ (block_size >> 8) as u8
+            ^ (block_size >> 16) as u8;
+        if checksum != expected {
+            return Err(DecompressionError::InvalidBlockHeader);
+        }
+
+        let block_start = reader.byte_position();
+        let bit_size = ((flags & 7) + 1) as usize;
+        let end_bit = ((block_start + block_size) * 8 + bit_size).saturating_sub(8);
+        debug!(
+            "[CompressedBlockHeader::read] Block at {} size {} flags {:#04x}",
+            block_start, block_size, flags
+        );
+
+        Ok(CompressedBlockHeader {
+            end_byte: block_start + block_size,
+            end_bit,
+            last_block_in_file: (flags & 0x40) != 0,
+            table_present: (flags & 0x80) != 0,
+        })
+    }
+}
+
+/// The four Huffman tables used to decode one or more blocks.
+struct BlockTables {
+    main: HuffmanTable,
+    distance: HuffmanTable,
+    low_distance: HuffmanTable,
+    repeat: HuffmanTable,
+}
+
+impl BlockTables {
+    fn read(reader: &mut BitReader, distance_codes: usize) -> Result<Self, DecompressionError> {
+        let mut bit_lengths = [0u8; BIT_LENGTH_CODES];
+        let mut index = 0;
+        while index < BIT_LENGTH_CODES {
+            let length = reader.read_bits(4) as u8;
+            if length == 15 {
+                let zero_count = reader.read_bits(4) as usize;
+                if zero_count == 0 {
+                    bit_lengths[index] = 15;
+                    index += 1;
+                } else {
+                    index = (index + zero_count + 2).min(BIT_LENGTH_CODES);
+                }
+            } else {
+                bit_lengths[index] = length;
+                index += 1;
+            }
+        }
+        let bit_length_table = HuffmanTable::new(&bit_lengths);
+
+        let table_size = MAIN_CODES + distance_codes + LOW_DISTANCE_CODES + REPEAT_CODES;
+        let mut lengths = vec![0u8; table_size];
+        let mut index = 0;
+        while index < table_size {
+            let symbol = bit_length_table.decode(reader);
+            if symbol < 16 {
+                lengths[index] = symbol as u8;
+                index += 1;
+                continue;
+            }
+            let count = if symbol == 16 || symbol == 18 {
+                reader.read_bits(3) as usize + 3
+            } else {
+                reader.read_bits(7) as usize + 11
+            };
+            let end = (index + count).min(table_size);
+            if symbol < 18 {
+                if index == 0 {
+                    return Err(DecompressionError::InvalidHuffmanTable);
+                }
+                let previous = lengths[index - 1];
+                lengths[index..end].fill(previous);
+            }
+            index = end;
+        }
+
+        let (main, rest) = lengths.split_at(MAIN_CODES);
+        let (distance, rest) = rest.split_at(distance_codes);
+        let (low_distance, repeat) = rest.split_at(LOW_DISTANCE_CODES);
+        Ok(BlockTables {
+            main: HuffmanTable::new(main),
+            distance: HuffmanTable::new(distance),
+            low_distance: HuffmanTable::new(low_distance),
+            repeat: HuffmanTable::new(repeat),
+        })
+    }
+}
+
+/// The sliding dictionary. It grows on demand up to its nominal size so small
+/// files do not pay for a large dictionary.
+struct Window {
+    buffer: Vec<u8>,
+    size: usize,
+    total_written: u64,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        Window {
+            buffer: Vec::new(),
+            size,
+            total_written: 0,
+        }
+    }
+
+    fn put(&mut self, byte: u8) {
+        let index = (self.total_written % self.size as u64) as usize;
+        if index == self.buffer.len() {
+            self.buffer.push(byte);
+        } else {
+            self.buffer[index] = byte;
+        }
+        self.total_written += 1;
+    }
+
+    /// Returns the byte at an absolute stream position. Positions before the
+    /// start of the stream read as zero, like an untouched dictionary.
+    fn byte_at(&self, position: u64) -> u8 {
+        self.buffer
+            .get((position % self.size as u64) as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn copy_match(&mut self, length: u32, distance: u64) -> Result<(), DecompressionError> {
+        if distance == 0 || distance > self.size as u64 {
+            return Err(DecompressionError::InvalidDistance);
+        }
+        for _ in 0..length {
+            let byte = match self.total_written.checked_sub(distance) {
+                Some(source) => self.byte_at(source),
+                None => 0,
+            };
+            self.put(byte);
+        }
+        Ok(())
+    }
+
+    fn copy_range(&self, start: u64, end: u64, output: &mut Vec<u8>) {
+        output.extend((start..end).map(|position| self.byte_at(position)));
+    }
+}
+
+/// What stopped a round of decoding.
+enum Progress {
+    /// Decoded bytes are ready to be handed out.
+    Output,
+    /// More input is needed to go on.
+    NeedInput,
+    /// The file's last block has been decoded.
+    FileEnd,
+}
+
+/// Decodes RAR5 LZSS + Huffman streams (compression methods 1 to 5).
+///
+/// Input is fed in pieces and output read in pieces, so memory use is bounded
+/// by the dictionary rather than by the size of the file.
+pub struct LzDecoder {
+    window: Window,
+    distance_codes: usize,
+    flushed: u64,
+    /// Stream position where the file currently being decoded starts.
+    file_start: u64,
+    old_distances: [u64; 4],
+    last_length: u32,
+    tables: Option<BlockTables>,
+    filters: VecDeque<Filter>,
+    /// Buffered input of the current file, read from `input_bit` on.
+    input: Vec<u8>,
+    input_bit: usize,
+    /// The last piece of the file's input has been fed.
+    input_complete: bool,
+    /// The block being decoded, positioned within `input`.
+    block: Option<CompressedBlockHeader>,
+    file_done: bool,
+    /// Flushed output not yet read, from `pending_position` on.
+    pending: Vec<u8>,
+    pending_position: usize,
+}
+
+impl LzDecoder {
+    /// Creates a decoder with the given dictionary size. `extended_distances`
+    /// selects the larger distance alphabet introduced by RAR7.
+    pub fn new(dictionary_size: usize, extended_distances: bool) -> Self {
+        LzDecoder {
+            window: Window::new(dictionary_size.max(MIN_WINDOW_SIZE)),
+            distance_codes: if extended_distances {
+                EXTENDED_DISTANCE_CODES
+            } else {
+                DISTANCE_CODES
+            },
+            flushed: 0,
+            file_start: 0,
+            old_distances: [0; 4],
+            last_length: 0,
+            tables: None,
+            filters: VecDeque::new(),
+            input: Vec::new(),
+            input_bit: 0,
+            input_complete: false,
+            block: None,
+            file_done: false,
+            pending: Vec::new(),
+            pending_position: 0,
+        }
+    }
+
+    /// Starts decoding the next file. The dictionary, tables and recent
+    /// distances carry over, as a solid stream needs.
+    pub fn start_file(&mut self) {
+        // Filters never span files, even in a solid stream.
+        self.filters.clear();
+        self.file_start = self.window.total_written;
+        self.input.clear();
+        self.input_bit = 0;
+        self.input_complete = false;
+        self.block = None;
+        self.file_done = false;
+        self.pending.clear();
+        self.pending_position = 0;
+    }
+
+    /// Buffers the next piece of the file's compressed stream. `is_last`
+    /// marks the end of the stream.
+    pub fn feed(&mut self, data: &[u8], is_last: bool) {
+        let consumed = self.input_bit / 8;
+        if consumed > 0 {
+            self.input.drain(..consumed);
+            self.input_bit -= consumed * 8;
+            if let Some(block) = &mut self.block {
+                block.end_byte -= consumed;
+                block.end_bit -= consumed * 8;
+            }
+        }
+        self.input.extend_from_slice(data);
+        self.input_complete |= is_last;
+    }
+
+    /// Copies decoded bytes to `output`. Returns 0 once the file is complete,
+    /// or when more input must be fed first.
+    pub fn read(&mut self, output: &mut [u8]) -> Result<usize, DecompressionError> {
+        loop {
+            let pending = &self.pending[self.pending_position..];
+            if !pending.is_empty() {
+                let count = pending.len().min(output.len());
+                output[..count].copy_from_slice(&pending[..count]);
+                self.pending_position += count;
+                return Ok(count);
+            }
+            self.pending.clear();
+            self.pending_position = 0;
+            if self.file_done {
+                return Ok(0);
+            }
+
+            let input = std::mem::take(&mut self.input);
+            let mut reader = BitReader::new(&input);
+            reader.set_bit_position(self.input_bit);
+            let progress = self.decode_some(&mut reader, input.len());
+            self.input_bit = reader.bit_position();
+            self.input = input;
+
+            match progress? {
+                Progress::NeedInput => return Ok(0),
+                Progress::Output => {}
+                Progress::FileEnd => {
+                    let mut pending = std::mem::take(&mut self.pending);
+                    self.flush(&mut pending);
+                    // A filter whose block runs past the end of the file is dropped.
+                    self.filters.clear();
+                    self.flush(&mut pending);
+                    self.pending = pending;
+                    self.file_done = true;
+                }
+            }
+        }
+    }
+
+    /// Decodes symbols until a chunk of output is ready, the input runs short
+    /// or the file ends. `available` is the number of buffered input bytes.
+    fn decode_some(&mut self, reader: &mut BitReader, available: usize) -> Result<Progress, DecompressionError> {
+        loop {
+            let Some(block) = &self.block else {
+                let header_start = reader.bit_position();
+                if !self.input_complete && reader.byte_position() + MAX_BLOCK_HEADER_SIZE > available {
+                    return Ok(Progress::NeedInput);
+                }
+                let block = CompressedBlockHeader::read(reader)?;
+                let needed = block.end_byte.min(reader.byte_position() + INPUT_LOOKAHEAD);
+                if !self.has_input(available, needed, block.end_byte)? {
+                    // Read the header again once the tables are buffered.
+                    reader.set_bit_position(header_start);
+                    return Ok(Progress::NeedInput);
+                }
+                if block.table_present {
+                    self.tables = Some(BlockTables::read(reader, self.distance_codes)?);
+                }
+                if self.tables.is_none() {
+                    return Err(DecompressionError::InvalidHuffmanTable);
+                }
+                self.block = Some(block);
+                continue;
+            };
+
+            if reader.bit_position() >= block.end_bit {
+                if block.last_block_in_file {
+                    return Ok(Progress::FileEnd);
+                }
+                self.block = None;
+                continue;
+            }
+            let needed = block.end_byte.min(reader.byte_position() + INPUT_LOOKAHEAD);
+            if !self.has_input(available, needed, block.end_byte)? {
+                return Ok(Progress::NeedInput);
+            }
+
+            if self.window.total_written - self.flushed >= OUTPUT_CHUNK || self.window_is_full() {
+                let mut pending = std::mem::take(&mut self.pending);
+                self.flush(&mut pending);
+                self.pending = pending;
+                if self.window_is_full() {
+                    // Only a filter block larger than allowed stays pending this long.
+                    return Err(DecompressionError::InvalidFilter);
+                }
+                // Output held back by an unfinished filter block needs more symbols.
+                if !self.pending.is_empty() {
+                    return Ok(Progress::Output);
+                }
+            }
+
+            self.decode_symbol(reader)?;
+        }
+    }
+
+    /// Returns true if the input up to byte `needed` is buffered. Once all
+    /// input has been fed, a block running past its end is an error.
+    fn has_input(&self, available: usize, needed: usize, block_end: usize) -> Result<bool, DecompressionError> {
+        if self.input_complete && block_end > available {
+            return Err(DecompressionError::UnexpectedEof);
+        }
+        Ok(needed <= available)
+    }
+
+    fn decode_symbol(&mut self, reader: &mut BitReader) -> Result<(), DecompressionError> {
+        let tables = self.tables.as_ref().ok_or(DecompressionError::InvalidHuffmanTable)?;
+        let main_slot = tables.main.decode(reader) as usize;
+
+        match main_slot {
+            0..=255 => {
+                self.window.put(main_slot as u8);
+                Ok(())
+            }
+            256 => {
+                let filter = Filter::read(reader, self.window.total_written)?;
+                self.add_filter(filter);
+                Ok(())
+            }
+            257 => {
+                if self.last_length != 0 {
+                    self.window.copy_match(self.last_length, self.old_distances[0])?;
+                }
+                Ok(())
+            }
+            258..=261 => {
+                let index = main_slot - 258;
+                let distance = self.old_distances[index];
+                self.old_distances.copy_within(0..index, 1);
+                self.old_distances[0] = distance;
+
+                let length_slot = tables.repeat.decode(reader) as u32;
+                let length = slot_to_length(reader, length_slot);
+                self.last_length = length;
+                self.window.copy_match(length, distance)
+            }
+            _ => {
+                let mut length = slot_to_length(reader, (main_slot - 262) as u32);
+                let distance = decode_distance(reader, tables);
+                if distance > 0x100 {
+                    length += 1;
+                    if distance > 0x2000 {
+                        length += 1;
+                        if distance > 0x40000 {
+                            length += 1;
+                        }
+                    }
+                }
+                self.old_distances.copy_within(0..3, 1);
+                self.old_distances[0] = distance;
+                self.last_length = length;
+                self.window.copy_match(length, distance)
+            }
+        }
+    }
+
+    fn add_filter(&mut self, filter: Filter) {
+        debug!("[add_filter] Queued {:?}", filter);
+        if self.filters.len() >= MAX_PENDING_FILTERS {
+            self.filters.clear();
+        }
+        self.filters.push_back(filter);
+    }
+
+    /// Returns true if the next match could overwrite bytes not yet flushed.
+    fn window_is_full(&self) -> bool {
+        self.window.total_written - self.flushed + MAX_MATCH_LENGTH >= self.window.size as u64
+    }
+
+    /// Writes decoded bytes to `output`, applying queued filters to their
+    /// blocks. Output stops at the start of a filter whose block is not fully
+    /// decoded yet.
+    fn flush(&mut self, output: &mut Vec<u8>) {
+        let end = self.window.total_written;
+        while let Some(filter) = self.filters.front().copied() {
+            if filter.block_start < self.flushed {
+                debug!("[flush] Dropping filter behind the output position: {:?}", filter);
+                self.filters.pop_front();
+                continue;
+            }
+            if filter.block_end() > end {
+                self.window.copy_range(self.flushed, filter.block_start.min(end), output);
+                self.flushed = filter.block_start.min(end);
+                return;
+            }
+
+            self.window.copy_range(self.flushed, filter.block_start, output);
+            let mut block = Vec::with_capacity(filter.block_length);
+            self.window.copy_range(filter.block_start, filter.block_end(), &mut block);
+            filter.kind.apply(&mut block, filter.block_start - self.file_start);
+            output.extend_from_slice(&block);
+            self.flushed = filter.block_end();
+            self.filters.pop_front();
+        }
+        self.window.copy_range(self.flushed, end, output);
+        self.flushed = end;
+    }
+}
+
+fn slot_to_length(reader: &mut BitReader, slot: u32) -> u32 {
+    if slot < 8 {
+        return 2 + slot;
+    }
+    let extra_bits = slot / 4 - 1;
+    2 + ((4 | (slot & 3)) << extra_bits) + reader.read_bits(extra_bits) as u32
+}
+
+fn decode_distance(reader: &mut BitReader, tables: &BlockTables) -> u64 {
+    let slot = tables.distance.decode(reader) as u64;
+    if slot < 4 {
+        return 1 + slot;
+    }
+    let extra_bits = (slot / 2 - 1) as u32;
+    let mut distance = 1 + ((2 | (slot & 1)) << extra_bits);
+    if extra_bits >= 4 {
+        distance += reader.read_bits(extra_bits - 4) << 4;
+        distance += tables.low_distance.decode(reader) as u64;
+    } else {
+        distance += reader.read_bits(extra_bits);
+    }
+    distance
+}
@@ -0,0 +1,207 @@
+// src/decompression/mod.rs
+
+pub mod bit_reader;
+pub mod filters;
+pub mod huffman;
+pub mod lz;
+
+use crate::decompression::lz::LzDecoder;
+use crate::structures::compression_info::{CompressionInfo, VERSION_RAR5, VERSION_RAR7};
+
+/// The largest dictionary RAR7 archives may use (64 GiB).
+const MAX_DICTIONARY_SIZE: u64 = 64 * 1024 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecompressionError {
+    InvalidFormat,
+    InvalidBlockHeader,
+    InvalidHuffmanTable,
+    InvalidDistance,
+    InvalidFilter,
+    UnexpectedEof,
+    UnsupportedVersion(u64),
+    UnsupportedMethod(u64),
+    DictionaryTooLarge(u64),
+}
+
+/// The size of the buffer `Decompressor::decompress` reads output through.
+const DECOMPRESS_CHUNK: usize = 64 * 1024;
+
+/// Decompresses the data area of one entry at a time, incrementally: packed
+/// data is fed in pieces and output is read in pieces. Implementations may
+/// keep state between entries so that entries of a solid stream share one
+/// dictionary.
+pub trait Decompressor {
+    /// Starts the next entry.
+    fn start_entry(&mut self);
+
+    /// Hands over the next piece of the entry's packed data. `is_last` marks
+    /// the final piece.
+    fn feed(&mut self, data: &[u8], is_last: bool);
+
+    /// Writes unpacked bytes to `output` and returns how many were written.
+    /// Returns 0 when more input must be fed, or, once the final piece has
+    /// been fed, when the entry is complete.
+    fn read(&mut self, output: &mut [u8]) -> Result<usize, DecompressionError>;
+
+    /// Decompresses a whole entry at once.
+    fn decompress(&mut self, data: &[u8]) -> Result<Vec<u8>, DecompressionError> {
+        self.start_entry();
+        self.feed(data, true);
+        let mut output = Vec::new();
+        let mut chunk = vec![0; DECOMPRESS_CHUNK];
+        loop {
+            match self.read(&mut chunk)? {
+                0 => return Ok(output),
+                count => output.extend_from_slice(&chunk[..count]),
+            }
+        }
+    }
+}
+
+/// The decompressor of stored entries, which passes data through as-is.
+#[derive(Default)]
+pub struct DummyDecompressor {
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl Decompressor for DummyDecompressor {
+    fn start_entry(&mut self) {
+        self.buffer.clear();
+        self.position = 0;
+    }
+
+    fn feed(&mut self, data: &[u8], _is_last: bool) {
+        self.buffer.drain(..self.position);
+        self.position = 0;
+        self.buffer.extend_from_slice(data);
+    }
+
+    fn read(&mut self, output: &mut [u8]) -> Result<usize, DecompressionError> {
+        let available = &self.buffer[self.position..];
+        let count = available.len().min(output.len());
+        output[..count].copy_from_slice(&available[..count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Decompresses RAR5 and RAR7 LZ streams (methods 1 "fastest" to 5 "best").
+///
+/// The dictionary survives between entries, so consecutive entries of a
+/// solid stream can be decoded one after the other.
+pub struct Rar5Decompressor {
+    decoder: LzDecoder,
+}
+
+impl Rar5Decompressor {
+    pub fn new(dictionary_size: usize, extended_distances: bool) -> Self {
+        Rar5Decompressor {
+            decoder: LzDecoder::new(dictionary_size, extended_distances),
+        }
+    }
+}
+
+impl Decompressor for Rar5Decompressor {
+    fn start_entry(&mut self) {
+        self.decoder.start_file();
+    }
+
+    fn feed(&mut self, data: &[u8], is_last: bool) {
+        self.decoder.feed(data, is_last);
+    }
+
+    fn read(&mut self, output: &mut [u8]) -> Result<usize, DecompressionError> {
+        self.decoder.read(output)
+    }
+}
+
+/// Selects a decompressor for an entry, rejecting algorithm versions, methods
+/// and dictionary sizes this crate cannot decode.
+pub fn get_decompressor(compression_info: CompressionInfo) -> Result<Box<dyn Decompressor>, DecompressionError> {
+    if compression_info.version() != VERSION_RAR5 && compression_info.version() != VERSION_RAR7 {
+        return Err(DecompressionError::UnsupportedVersion(compression_info.version()));
+    }
+    match compression_info.method() {
+        0 => Ok(Box::<DummyDecompressor>::default()),
+        1..=5 => {
+            let dictionary_size = compression_info.dictionary_size();
+            if dictionary_size > MAX_DICTIONARY_SIZE {
+                return Err(DecompressionError::DictionaryTooLarge(dictionary_size));
+            }
+            let dictionary_size = usize::try_from(dictionary_size)
+                .map_err(|_| DecompressionError::DictionaryTooLarge(dictionary_size))?;
+            Ok(Box::new(Rar5Decompressor::new(
+                dictionary_size,
+                compression_info.uses_extended_distances(),
+            )))
+        }
+        method => Err(DecompressionError::UnsupportedMethod(method)),
+    }
+}
+
+pub fn decompress_data(compressed_data: &[u8], compression_info: CompressionInfo) -> Result<Vec<u8>, DecompressionError> {
+    let mut decompressor = get_decompressor(compression_info)?;
+    decompressor.decompress(compressed_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dummy_decompressor_returns_input_as_is() {
+        let mut decompressor = DummyDecompressor::default();
+        let test_data = vec![10, 20, 30, 40, 50];
+        let result = decompressor.decompress(&test_data).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn get_decompressor_returns_dummy_decompressor() {
+        let mut decompressor = get_decompressor(CompressionInfo::default()).unwrap();
+        let test_data = vec![1, 2, 3];
+        let result = decompressor.decompress(&test_data).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn dummy_decompressor_streams_pieces() {
+        let mut decompressor = DummyDecompressor::default();
+        decompressor.start_entry();
+        decompressor.feed(&[1, 2, 3], false);
+        let mut output = [0u8; 2];
+        assert_eq!(decompressor.read(&mut output).unwrap(), 2);
+        assert_eq!(output, [1, 2]);
+        decompressor.feed(&[4], true);
+        assert_eq!(decompressor.read(&mut output).unwrap(), 2);
+        assert_eq!(output, [3, 4]);
+        assert_eq!(decompressor.read(&mut output).unwrap(), 0);
+    }
+
+    #[test]
+    fn get_decompressor_rejects_garbage_for_compressed_methods() {
+        let mut decompressor = get_decompressor(CompressionInfo::new(3 << 7)).unwrap();
+        assert!(decompressor.decompress(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn get_decompressor_rejects_unknown_versions() {
+        let result = get_decompressor(CompressionInfo::new((3 << 7) | 2));
+        assert_eq!(result.err(), Some(DecompressionError::UnsupportedVersion(2)));
+    }
+
+    #[test]
+    fn get_decompressor_rejects_unknown_methods() {
+        let result = get_decompressor(CompressionInfo::new(6 << 7));
+        assert_eq!(result.err(), Some(DecompressionError::UnsupportedMethod(6)));
+    }
+
+    #[test]
+    fn get_decompressor_rejects_oversized_dictionaries() {
+        // RAR7, exponent 20: 128 KiB << 20 = 128 GiB.
+        let result = get_decompressor(CompressionInfo::new((20 << 10) | (3 << 7) | 1));
+        assert_eq!(result.err(), Some(DecompressionError::DictionaryTooLarge(128 << 30)));
+    }
+}
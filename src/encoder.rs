@@ -1,7 +1,9 @@
 // src/encoder.rs
 
+use crate::checksum::crc32;
 use crate::structures::base::BlockHeader;
 use crate::structures::block::Block;
+use crate::structures::general_block_header::{GeneralBlockHeader, HeaderFlags};
 use std::io::{self, Write};
 use log::debug;
 
@@ -25,6 +27,58 @@ pub fn encode_block(block: &BlockHeader, writer: &mut dyn Write) -> io::Result<(
     match block {
         BlockHeader::Main(header) => header.encode(writer),
         BlockHeader::File(header) => header.encode(writer),
-        BlockHeader::End(header) => header.encode(writer),
+        BlockHeader::Service(header) => header.encode(writer),
+        BlockHeader::Encryption(header) => header.encode(writer),
+        BlockHeader::End(header) | BlockHeader::VolumeEnd(header) => header.encode(writer),
+        BlockHeader::Unknown { general_header, raw } => encode_raw_block(general_header, raw, writer),
     }
 }
+
+/// Writes a block from its general header and the raw bytes that follow the
+/// general fields, as kept for unknown block types.
+fn encode_raw_block(general_header: &GeneralBlockHeader, raw: &[u8], writer: &mut dyn Write) -> io::Result<()> {
+    frame_header(
+        writer,
+        general_header.header_type,
+        general_header.header_flags,
+        general_header.extra_area_size,
+        general_header.data_size,
+        raw,
+    )
+}
+
+/// Writes a block header around `body`, the type-specific fields followed by
+/// the extra area: the CRC32 and header size, then the type, the flags and the
+/// area sizes. The area flags follow from which sizes are given.
+pub(crate) fn frame_header(
+    writer: &mut dyn Write,
+    header_type: u64,
+    header_flags: HeaderFlags,
+    extra_area_size: Option<u64>,
+    data_size: Option<u64>,
+    body: &[u8],
+) -> io::Result<()> {
+    let header_flags = header_flags
+        .with(HeaderFlags::EXTRA_AREA, extra_area_size.is_some())
+        .with(HeaderFlags::DATA_AREA, data_size.is_some());
+
+    let mut fields = Vec::new();
+    write_vint(&mut fields, header_type)?;
+    write_vint(&mut fields, header_flags.0)?;
+    // Extra area size comes before the data size
+    if let Some(extra_area_size) = extra_area_size {
+        write_vint(&mut fields, extra_area_size)?;
+    }
+    if let Some(data_size) = data_size {
+        write_vint(&mut fields, data_size)?;
+    }
+    fields.extend_from_slice(body);
+
+    let mut header = Vec::new();
+    write_vint(&mut header, fields.len() as u64)?;
+    header.extend_from_slice(&fields);
+
+    // The CRC32 covers the header size field and the rest of the header
+    writer.write_all(&crc32(&header).to_le_bytes())?;
+    writer.write_all(&header)
+}
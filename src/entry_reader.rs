@@ -0,0 +1,160 @@
+// src/entry_reader.rs
+
+//! Streaming access to the unpacked data of one entry, returned by
+//! `Archive::entry_reader`.
+
+use crate::archive::{decompression_error, Archive};
+use crate::crypto::{CbcDecryptor, BLOCK_SIZE};
+use crate::decompression::Decompressor;
+use crate::integrity::EntryVerifier;
+use crate::structures::file_header::FileHeader;
+use log::debug;
+use std::io::{self, Read};
+
+/// How much packed data is read from the archive at a time.
+const INPUT_CHUNK: u64 = 64 * 1024;
+
+/// Reads an entry's data area in chunks, decrypting and decompressing it on
+/// the way, so memory use does not grow with the size of the entry.
+///
+/// Reaching the end of the data checks it against the stored checksums; a
+/// mismatch is returned as an error from the final `read`.
+pub struct EntryReader<'a, R: Read> {
+    archive: &'a mut Archive<R>,
+    /// The header of the part whose data is being read.
+    part: FileHeader,
+    decompressor: Option<Box<dyn Decompressor>>,
+    /// Whether the decompressor goes back to the archive for the next solid entry.
+    keeps_solid_state: bool,
+    decryptor: Option<CbcDecryptor>,
+    /// Encrypted bytes short of a whole AES block, kept for the next chunk.
+    cipher_carry: Vec<u8>,
+    input: Vec<u8>,
+    /// Output still to be returned when padding has to be cut off.
+    remaining_output: Option<u64>,
+    verifier: Option<EntryVerifier>,
+    input_done: bool,
+    finished: bool,
+}
+
+impl<'a, R: Read> EntryReader<'a, R> {
+    pub(crate) fn new(
+        archive: &'a mut Archive<R>,
+        part: FileHeader,
+        decompressor: Box<dyn Decompressor>,
+        keeps_solid_state: bool,
+        decryptor: Option<CbcDecryptor>,
+        verifier: Option<EntryVerifier>,
+        remaining_output: Option<u64>,
+    ) -> Self {
+        EntryReader {
+            archive,
+            part,
+            decompressor: Some(decompressor),
+            keeps_solid_state,
+            decryptor,
+            cipher_carry: Vec::new(),
+            input: Vec::new(),
+            remaining_output,
+            verifier,
+            input_done: false,
+            finished: false,
+        }
+    }
+
+    /// Passes the next chunk of packed data to the decompressor, moving on to
+    /// the next volume when the current part of a split entry is used up.
+    fn feed_input(&mut self) -> io::Result<()> {
+        while self.archive.remaining_data() == 0 && self.part.base.general_header.header_flags.is_split_after() {
+            self.part = self.archive.next_data_part(&self.part.file_name)?;
+            if let Some(verifier) = &mut self.verifier {
+                verifier.expect_checksums_of(&self.part)?;
+            }
+        }
+
+        let size = self.archive.remaining_data().min(INPUT_CHUNK) as usize;
+        self.input.clear();
+        self.input.append(&mut self.cipher_carry);
+        let start = self.input.len();
+        self.input.resize(start + size, 0);
+        self.archive.read_data(&mut self.input[start..])?;
+        let is_last = self.archive.remaining_data() == 0 && !self.part.base.general_header.header_flags.is_split_after();
+
+        if let Some(decryptor) = &mut self.decryptor {
+            let whole_blocks = self.input.len() - self.input.len() % BLOCK_SIZE;
+            if is_last && whole_blocks != self.input.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Encrypted data of '{}' is not a whole number of AES blocks", self.part.file_name),
+                ));
+            }
+            self.cipher_carry.extend_from_slice(&self.input[whole_blocks..]);
+            self.input.truncate(whole_blocks);
+            decryptor.decrypt(&mut self.input);
+        }
+
+        self.decompressor
+            .as_mut()
+            .expect("decompressor is kept until the entry is finished")
+            .feed(&self.input, is_last);
+        self.input_done = is_last;
+        Ok(())
+    }
+
+    /// Hands the solid decoder state back to the archive and verifies the data.
+    fn finish(&mut self) -> io::Result<()> {
+        self.finished = true;
+        if self.keeps_solid_state {
+            if let Some(decompressor) = self.decompressor.take() {
+                self.archive.restore_solid_decompressor(decompressor);
+            }
+        }
+        debug!("[EntryReader::finish] Finished '{}'", self.part.file_name);
+        if let Some(verifier) = self.verifier.take() {
+            verifier.finish()?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for EntryReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.finished || buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let produced = self
+                .decompressor
+                .as_mut()
+                .expect("decompressor is kept until the entry is finished")
+                .read(buf)
+                .map_err(decompression_error)?;
+
+            if produced == 0 {
+                if self.input_done {
+                    self.finish()?;
+                    return Ok(0);
+                }
+                self.feed_input()?;
+                continue;
+            }
+
+            let count = match &mut self.remaining_output {
+                Some(remaining) => {
+                    let count = produced.min(usize::try_from(*remaining).unwrap_or(usize::MAX));
+                    *remaining -= count as u64;
+                    count
+                }
+                None => produced,
+            };
+            if count == 0 {
+                // Only padding is left; drain it to reach the end of the data.
+                continue;
+            }
+            if let Some(verifier) = &mut self.verifier {
+                verifier.update(&buf[..count]);
+            }
+            return Ok(count);
+        }
+    }
+}
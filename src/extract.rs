@@ -0,0 +1,366 @@
+// src/extract.rs
+
+use crate::structures::file_header::FileHeader;
+use crate::structures::redirection::{Redirection, RedirectionKind};
+use log::debug;
+use std::fs;
+#[cfg(not(unix))]
+use std::fs::{File, FileTimes};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Writes archive entries below a destination directory.
+pub struct Extractor {
+    destination: PathBuf,
+    apply_file_times: bool,
+    restore_owner: bool,
+}
+
+impl Extractor {
+    pub fn new<P: Into<PathBuf>>(destination: P) -> Self {
+        Extractor {
+            destination: destination.into(),
+            apply_file_times: true,
+            restore_owner: false,
+        }
+    }
+
+    /// Controls whether the entry's modification and access times are set on
+    /// the files created. Enabled by default.
+    pub fn set_apply_file_times(&mut self, apply_file_times: bool) {
+        self.apply_file_times = apply_file_times;
+    }
+
+    /// Controls whether created files are given the user and group stored in
+    /// the entry's Unix owner record. Names are preferred over numeric ids.
+    /// Disabled by default; without the privileges to chown, ownership is left
+    /// unchanged.
+    pub fn set_restore_owner(&mut self, restore_owner: bool) {
+        self.restore_owner = restore_owner;
+    }
+
+    /// Creates the file, directory or link for an entry from its unpacked data
+    /// and returns its path.
+    ///
+    /// Hard links and file copies refer to entries extracted earlier by the
+    /// same `Extractor`. Nothing is written through a symlink below the
+    /// destination, whether an earlier entry created it or it was already there.
+    pub fn extract(&self, header: &FileHeader, data: &[u8]) -> io::Result<PathBuf> {
+        let path = self.entry_path(&header.file_name)?;
+        debug!("[Extractor::extract] Extracting '{}' to {:?}", header.file_name, path);
+        if let Some(redirection) = header.redirection()? {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            self.create_redirection(&header.file_name, &path, &redirection)?;
+        } else if header.is_directory() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, data)?;
+        }
+
+        if self.restore_owner {
+            restore_owner(&path, header)?;
+        }
+        if self.apply_file_times {
+            apply_file_times(&path, header)?;
+        }
+        Ok(path)
+    }
+
+    fn create_redirection(&self, file_name: &str, path: &Path, redirection: &Redirection) -> io::Result<()> {
+        debug!("[Extractor::create_redirection] {:?} -> '{}'", redirection.kind, redirection.target);
+        match redirection.kind {
+            RedirectionKind::UnixSymlink | RedirectionKind::WindowsSymlink => {
+                let target = redirection.target.replace('\\', "/");
+                self.check_link_target(file_name, path, &target)?;
+                create_symlink(&target, path)
+            }
+            RedirectionKind::HardLink => fs::hard_link(self.entry_path(&redirection.target)?, path),
+            RedirectionKind::FileCopy => fs::copy(self.entry_path(&redirection.target)?, path).map(|_| ()),
+            RedirectionKind::WindowsJunction | RedirectionKind::Other(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Cannot extract {:?} redirection of '{}'", redirection.kind, file_name),
+            )),
+        }
+    }
+
+    /// Resolves an entry name below the destination, refusing names that
+    /// would escape it and paths that pass through a symlink.
+    fn entry_path(&self, file_name: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(file_name);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Refusing to extract unsafe path '{}'", file_name),
+            ));
+        }
+        let mut path = self.destination.clone();
+        for component in relative.components() {
+            path.push(component);
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Refusing to extract '{}' through the symlink {:?}", file_name, path),
+                    ))
+                }
+                Ok(_) => {}
+                // Nothing below a missing component can be a symlink yet.
+                Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(self.destination.join(relative))
+    }
+
+    /// Refuses symlink targets that are absolute or lead out of the
+    /// destination. The target is resolved from the link's real parent
+    /// directory, following the links already on disk. Past a component that
+    /// does not exist yet only plain names are accepted, as `..` there would
+    /// depend on what is created in its place later.
+    fn check_link_target(&self, file_name: &str, path: &Path, target: &str) -> io::Result<()> {
+        let outside = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Refusing to create link '{}' to '{}' outside the destination", file_name, target),
+            )
+        };
+        let root = fs::canonicalize(&self.destination)?;
+        let mut resolved = fs::canonicalize(path.parent().unwrap_or(&self.destination))?;
+        let mut missing = false;
+        for component in Path::new(target).components() {
+            match component {
+                Component::Normal(name) => {
+                    resolved.push(name);
+                    if !missing {
+                        match fs::canonicalize(&resolved) {
+                            Ok(canonical) => resolved = canonical,
+                            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                                // A dangling link could point anywhere.
+                                if fs::symlink_metadata(&resolved).is_ok() {
+                                    return Err(outside());
+                                }
+                                missing = true;
+                            }
+                            Err(e) => return Err(e),
+                        }
+                    }
+                }
+                Component::CurDir => {}
+                Component::ParentDir if !missing => {
+                    resolved.pop();
+                }
+                _ => return Err(outside()),
+            }
+            if !resolved.starts_with(&root) {
+                return Err(outside());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &str, _path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Symlinks can only be extracted on Unix",
+    ))
+}
+
+/// Gives `path` the owner recorded for the entry.
+///
+/// Names are looked up through the system's user and group databases, so
+/// NSS sources such as LDAP or sssd are consulted too. A name that is not
+/// known on this system falls back to the stored numeric id, as the archive
+/// may come from a machine with other accounts; a failing lookup is an error.
+#[cfg(unix)]
+pub fn restore_owner(path: &Path, header: &FileHeader) -> io::Result<()> {
+    let Some(owner) = header.unix_owner()? else {
+        return Ok(());
+    };
+    let user_id = match owner.user_name.as_deref() {
+        Some(name) => lookup_user_id(name)?,
+        None => None,
+    };
+    let user_id = user_id.or_else(|| owner.user_id.and_then(|id| u32::try_from(id).ok()));
+    let group_id = match owner.group_name.as_deref() {
+        Some(name) => lookup_group_id(name)?,
+        None => None,
+    };
+    let group_id = group_id.or_else(|| owner.group_id.and_then(|id| u32::try_from(id).ok()));
+
+    match std::os::unix::fs::lchown(path, user_id, group_id) {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            debug!("[restore_owner] Not permitted to chown {:?}: {}", path, e);
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn restore_owner(_path: &Path, _header: &FileHeader) -> io::Result<()> {
+    Ok(())
+}
+
+/// The largest buffer offered to `getpwnam_r` and `getgrnam_r`.
+#[cfg(unix)]
+const MAX_LOOKUP_BUFFER: usize = 1024 * 1024;
+
+#[cfg(unix)]
+type LookupFn<T> =
+    unsafe extern "C" fn(*const libc::c_char, *mut T, *mut libc::c_char, libc::size_t, *mut *mut T) -> libc::c_int;
+
+/// Finds the id of a user name, or `None` if there is no such user.
+#[cfg(unix)]
+fn lookup_user_id(name: &str) -> io::Result<Option<u32>> {
+    lookup_id(name, libc::getpwnam_r, |entry: &libc::passwd| entry.pw_uid)
+}
+
+/// Finds the id of a group name, or `None` if there is no such group.
+#[cfg(unix)]
+fn lookup_group_id(name: &str) -> io::Result<Option<u32>> {
+    lookup_id(name, libc::getgrnam_r, |entry: &libc::group| entry.gr_gid)
+}
+
+/// Runs a reentrant name lookup, growing the buffer while it is too small.
+#[cfg(unix)]
+fn lookup_id<T>(name: &str, lookup: LookupFn<T>, id: fn(&T) -> u32) -> io::Result<Option<u32>> {
+    let Ok(c_name) = std::ffi::CString::new(name) else {
+        return Ok(None);
+    };
+    let mut buffer: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: `T` is `passwd` or `group`, C structs for which all zero
+        // bytes are a valid value.
+        let mut entry: T = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        // SAFETY: every pointer is valid for the call and the buffer's length
+        // is passed with it; `entry` is only read when `result` points to it.
+        let error = unsafe { lookup(c_name.as_ptr(), &mut entry, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+        match error {
+            0 if result.is_null() => return Ok(None),
+            0 => return Ok(Some(id(&entry))),
+            libc::ERANGE if buffer.len() < MAX_LOOKUP_BUFFER => buffer.resize(buffer.len() * 2, 0),
+            // Some systems report an unknown name as one of these.
+            libc::ENOENT | libc::ESRCH | libc::EBADF | libc::EPERM => return Ok(None),
+            error => {
+                debug!("[lookup_id] Looking up '{}' failed with {}", name, error);
+                return Err(io::Error::from_raw_os_error(error));
+            }
+        }
+    }
+}
+
+/// Sets the modification and access times recorded for an entry on `path`.
+/// A symlink gets the times itself; its target is left alone.
+///
+/// The file time record is preferred; the header's Unix modification time is
+/// used when the record is absent.
+pub fn apply_file_times(path: &Path, header: &FileHeader) -> io::Result<()> {
+    let file_time = header.file_time()?.unwrap_or_default();
+    let mtime = file_time
+        .mtime
+        .or_else(|| header.mtime.map(|mtime| UNIX_EPOCH + Duration::from_secs(mtime as u64)));
+
+    if mtime.is_none() && file_time.atime.is_none() {
+        return Ok(());
+    }
+
+    set_times(path, mtime, file_time.atime)
+}
+
+/// Sets the times by path, so neither read access nor a link target is needed.
+#[cfg(unix)]
+fn set_times(path: &Path, mtime: Option<SystemTime>, atime: Option<SystemTime>) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let times = [timespec(atime), timespec(mtime)];
+    // SAFETY: `c_path` is NUL-terminated and `times` holds the two entries
+    // utimensat reads.
+    let result = unsafe { libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), libc::AT_SYMLINK_NOFOLLOW) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Converts a time for utimensat; `None` leaves that time unchanged.
+#[cfg(unix)]
+fn timespec(time: Option<SystemTime>) -> libc::timespec {
+    // SAFETY: timespec is plain data, and zero is valid for all its fields.
+    let mut timespec: libc::timespec = unsafe { std::mem::zeroed() };
+    let Some(time) = time else {
+        timespec.tv_nsec = libc::UTIME_OMIT;
+        return timespec;
+    };
+    let (seconds, nanoseconds) = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+        Err(e) => {
+            let before = e.duration();
+            match before.subsec_nanos() {
+                0 => (-(before.as_secs() as i64), 0),
+                nanoseconds => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanoseconds),
+            }
+        }
+    };
+    timespec.tv_sec = seconds as libc::time_t;
+    timespec.tv_nsec = nanoseconds as _;
+    timespec
+}
+
+#[cfg(not(unix))]
+fn set_times(path: &Path, mtime: Option<SystemTime>, atime: Option<SystemTime>) -> io::Result<()> {
+    let mut times = FileTimes::new();
+    if let Some(mtime) = mtime {
+        times = times.set_modified(mtime);
+    }
+    if let Some(atime) = atime {
+        times = times.set_accessed(atime);
+    }
+    File::options().write(true).open(path)?.set_times(times)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_ids_through_the_system_databases() {
+        assert_eq!(lookup_user_id("root").unwrap(), Some(0));
+        assert_eq!(lookup_group_id(&group_name_of_gid_0()).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn unknown_names_are_not_found() {
+        assert_eq!(lookup_user_id("no-such-user-for-extract-tests").unwrap(), None);
+        assert_eq!(lookup_group_id("no-such-group-for-extract-tests").unwrap(), None);
+        assert_eq!(lookup_user_id("nul\0name").unwrap(), None);
+    }
+
+    /// Group 0 is `root` on Linux and `wheel` on the BSDs.
+    fn group_name_of_gid_0() -> String {
+        // SAFETY: getgrgid returns a pointer to static storage or null; the
+        // name is copied out before any other lookup.
+        unsafe {
+            let group = libc::getgrgid(0);
+            assert!(!group.is_null());
+            std::ffi::CStr::from_ptr((*group).gr_name).to_string_lossy().into_owned()
+        }
+    }
+}
@@ -0,0 +1,120 @@
+// src/indexed_archive.rs
+
+use crate::archive::Archive;
+use crate::structures::base::BlockHeader;
+use crate::structures::file_header::FileHeader;
+use log::debug;
+use std::io::{self, Read, Seek};
+
+/// A file entry of an `IndexedArchive`.
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    pub header: FileHeader,
+    /// The offset of the entry's data area in the archive.
+    pub data_offset: u64,
+}
+
+/// Random access to the entries of a single-volume archive.
+///
+/// The headers are scanned once, seeking past the data areas, and entries are
+/// then read by seeking straight to their data. Entries of a solid stream are
+/// decoded from the start of the stream, or from the last entry read if it
+/// comes before them.
+pub struct IndexedArchive<R: Read + Seek> {
+    archive: Archive<R>,
+    entries: Vec<IndexEntry>,
+    /// The index of the last solid stream entry decoded, while the decoder
+    /// state still follows it.
+    solid_position: Option<usize>,
+}
+
+impl<R: Read + Seek> IndexedArchive<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        Self::from_archive(Archive::new(reader)?)
+    }
+
+    /// Builds the index from a freshly opened `Archive`, keeping its settings
+    /// such as the password and CRC mode.
+    pub fn from_archive(mut archive: Archive<R>) -> io::Result<Self> {
+        archive.seek_past_data();
+
+        let mut entries = Vec::new();
+        while let Some(block) = archive.next() {
+            if let BlockHeader::File(header) = block? {
+                let data_offset = archive.stream_position()?;
+                debug!("[IndexedArchive::new] '{}' at {}", header.file_name, data_offset);
+                entries.push(IndexEntry { header, data_offset });
+            }
+        }
+        Ok(IndexedArchive {
+            archive,
+            entries,
+            solid_position: None,
+        })
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Returns the index of the first entry named `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.header.file_name == name)
+    }
+
+    /// Returns the unpacked data of the first entry named `name`.
+    pub fn extract_by_name(&mut self, name: &str) -> io::Result<Vec<u8>> {
+        let index = self.position(name).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No entry named '{}'", name))
+        })?;
+        self.extract_by_index(index)
+    }
+
+    /// Returns the unpacked data of entry `index`.
+    pub fn extract_by_index(&mut self, index: usize) -> io::Result<Vec<u8>> {
+        if index >= self.entries.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No entry at index {}; the archive has {}", index, self.entries.len()),
+            ));
+        }
+        let compression_info = self.entries[index].header.compression_info;
+        if compression_info.is_stored() || !compression_info.is_solid() {
+            let result = self.read_entry(index);
+            if !compression_info.is_stored() {
+                self.solid_position = result.as_ref().ok().map(|_| index);
+            }
+            return result;
+        }
+
+        // A solid entry depends on every compressed entry since the stream
+        // started, unless the decoder has already been brought part of the way.
+        let start = match self.solid_position {
+            Some(position) if position < index => position + 1,
+            _ => self.entries[..index]
+                .iter()
+                .rposition(|entry| {
+                    let info = entry.header.compression_info;
+                    !info.is_stored() && !info.is_solid()
+                })
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "Solid stream has no first entry")
+                })?,
+        };
+        self.solid_position = None;
+        for earlier in start..index {
+            if !self.entries[earlier].header.compression_info.is_stored() {
+                debug!("[IndexedArchive::extract_by_index] Decoding earlier solid entry {}", earlier);
+                self.read_entry(earlier)?;
+            }
+        }
+        let data = self.read_entry(index)?;
+        self.solid_position = Some(index);
+        Ok(data)
+    }
+
+    fn read_entry(&mut self, index: usize) -> io::Result<Vec<u8>> {
+        let entry = &self.entries[index];
+        self.archive.read_file_data_at(&entry.header, entry.data_offset)
+    }
+}
@@ -0,0 +1,162 @@
+// src/integrity.rs
+
+use crate::blake2sp::{Blake2sp, DIGEST_SIZE};
+use crate::checksum::CRC32;
+use crate::crypto::{crc32_to_mac, digest_to_mac, KEY_SIZE};
+use crate::structures::file_hash::FileHash;
+use crate::structures::file_header::FileHeader;
+use crc::Digest;
+use log::debug;
+use std::fmt;
+use std::io;
+
+/// An entry's unpacked data does not match the checksum stored for it.
+///
+/// `Archive` reports it as an `InvalidData` I/O error wrapping this value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+        name: String,
+    },
+    HashMismatch {
+        expected: [u8; DIGEST_SIZE],
+        actual: [u8; DIGEST_SIZE],
+        name: String,
+    },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::ChecksumMismatch { expected, actual, name } => write!(
+                f,
+                "CRC32 mismatch for '{}': expected {:#010x}, got {:#010x}",
+                name, expected, actual
+            ),
+            IntegrityError::HashMismatch { expected, actual, name } => write!(
+                f,
+                "BLAKE2sp mismatch for '{}': expected {}, got {}",
+                name,
+                hex(expected),
+                hex(actual)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+impl From<IntegrityError> for io::Error {
+    fn from(error: IntegrityError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Checks an entry's unpacked data against the CRC32 and hash stored in its
+/// header as the data is produced.
+pub struct EntryVerifier {
+    name: String,
+    expected_crc32: Option<u32>,
+    crc32: Option<Digest<'static, u32>>,
+    expected_hash: Option<[u8; DIGEST_SIZE]>,
+    blake2sp: Option<Blake2sp>,
+    mac_key: Option<[u8; KEY_SIZE]>,
+}
+
+impl EntryVerifier {
+    pub fn new(header: &FileHeader) -> io::Result<Self> {
+        let expected_hash = expected_hash(header)?;
+        // The checksums of data split across volumes are in the last part's
+        // header, so both are computed until it is known which are stored.
+        let split_after = header.base.general_header.header_flags.is_split_after();
+        Ok(EntryVerifier {
+            name: header.file_name.clone(),
+            expected_crc32: header.data_crc32,
+            crc32: (split_after || header.data_crc32.is_some()).then(|| CRC32.digest()),
+            expected_hash,
+            blake2sp: (split_after || expected_hash.is_some()).then(Blake2sp::new),
+            mac_key: None,
+        })
+    }
+
+    /// Compares against checksums stored as MACs keyed by `hash_key`, as in
+    /// encrypted entries whose encryption record asks for it.
+    pub fn with_mac_key(mut self, hash_key: [u8; KEY_SIZE]) -> Self {
+        self.mac_key = Some(hash_key);
+        self
+    }
+
+    /// Takes the expected checksums from another part of a split entry. The
+    /// last part's header has those of the whole entry.
+    pub fn expect_checksums_of(&mut self, header: &FileHeader) -> io::Result<()> {
+        self.expected_crc32 = header.data_crc32;
+        self.expected_hash = expected_hash(header)?;
+        Ok(())
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        if let Some(digest) = &mut self.crc32 {
+            digest.update(data);
+        }
+        if let Some(hasher) = &mut self.blake2sp {
+            hasher.update(data);
+        }
+    }
+
+    /// Compares the checksums of everything passed to `update` with the
+    /// stored values.
+    pub fn finish(self) -> Result<(), IntegrityError> {
+        if let (Some(expected), Some(digest)) = (self.expected_crc32, self.crc32) {
+            let mut actual = digest.finalize();
+            if let Some(mac_key) = &self.mac_key {
+                actual = crc32_to_mac(mac_key, actual);
+            }
+            if actual != expected {
+                return Err(IntegrityError::ChecksumMismatch {
+                    expected,
+                    actual,
+                    name: self.name,
+                });
+            }
+        }
+        if let (Some(expected), Some(hasher)) = (self.expected_hash, self.blake2sp) {
+            let mut actual = hasher.finalize();
+            if let Some(mac_key) = &self.mac_key {
+                actual = digest_to_mac(mac_key, &actual);
+            }
+            if actual != expected {
+                return Err(IntegrityError::HashMismatch {
+                    expected,
+                    actual,
+                    name: self.name,
+                });
+            }
+        }
+        debug!("[EntryVerifier::finish] Verified '{}'", self.name);
+        Ok(())
+    }
+}
+
+fn expected_hash(header: &FileHeader) -> io::Result<Option<[u8; DIGEST_SIZE]>> {
+    match header.file_hash()? {
+        Some(FileHash::Blake2sp(expected)) => Ok(Some(expected)),
+        Some(FileHash::Unknown { hash_type, .. }) => {
+            debug!("[EntryVerifier] Unknown hash type {} for '{}'", hash_type, header.file_name);
+            Ok(None)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Checks unpacked data against the CRC32 and hash record of its header.
+pub fn verify_entry(header: &FileHeader, data: &[u8]) -> io::Result<()> {
+    let mut verifier = EntryVerifier::new(header)?;
+    verifier.update(data);
+    Ok(verifier.finish()?)
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -1,10 +1,20 @@
 // src/lib.rs
 
 pub mod structures;
+pub mod checksum;
+pub mod blake2sp;
+pub mod crypto;
+pub mod password;
 pub mod decoder;
 pub mod archive;
+pub mod entry_reader;
+pub mod indexed_archive;
 pub mod encoder;
 pub mod decompression;
+pub mod signature;
+pub mod extract;
+pub mod volume;
+pub mod integrity;
 
 #[cfg(test)]
 mod tests {
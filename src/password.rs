@@ -0,0 +1,138 @@
+// src/password.rs
+
+//! Passwords for encrypted archives and the callback `Archive` uses to ask
+//! for them.
+
+use std::fmt;
+use std::io;
+use zeroize::Zeroizing;
+
+/// A password, held in a buffer that is zeroed when dropped.
+#[derive(Clone)]
+pub struct Password(Zeroizing<Vec<u8>>);
+
+impl Password {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Password(Zeroizing::new(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&str> for Password {
+    fn from(password: &str) -> Self {
+        Password::new(password.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Password {
+    fn from(password: String) -> Self {
+        Password::new(password.into_bytes())
+    }
+}
+
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(..)")
+    }
+}
+
+/// What a password is needed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordTarget<'a> {
+    /// The block headers of an archive with an encryption header.
+    Headers,
+    /// The data of the named entry.
+    Entry(&'a str),
+}
+
+impl fmt::Display for PasswordTarget<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordTarget::Headers => f.write_str("the archive headers"),
+            PasswordTarget::Entry(name) => write!(f, "'{}'", name),
+        }
+    }
+}
+
+/// A request for a password, passed to `PasswordProvider::password`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordRequest<'a> {
+    pub target: PasswordTarget<'a>,
+    /// How many passwords have already been rejected for this target.
+    pub failed_attempts: u32,
+}
+
+/// The answer to a `PasswordRequest`.
+#[derive(Debug, Clone)]
+pub enum PasswordResponse {
+    Password(Password),
+    /// Give up on the target; `Archive` fails with `PasswordCancelled`.
+    Cancel,
+}
+
+/// Supplies passwords when `Archive` meets an encryption header or an
+/// encrypted entry it has no keys for.
+///
+/// It is asked again after a wrong password, up to the archive's attempt
+/// limit. Closures taking a `&PasswordRequest` implement it.
+pub trait PasswordProvider {
+    fn password(&mut self, request: &PasswordRequest) -> PasswordResponse;
+}
+
+impl<F: FnMut(&PasswordRequest) -> PasswordResponse> PasswordProvider for F {
+    fn password(&mut self, request: &PasswordRequest) -> PasswordResponse {
+        self(request)
+    }
+}
+
+/// The provider cancelled a password request.
+///
+/// `Archive` reports it as a `PermissionDenied` I/O error wrapping this value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordCancelled {
+    pub target: String,
+}
+
+impl PasswordCancelled {
+    /// Returns true if `error` wraps a `PasswordCancelled`.
+    pub fn is_cause_of(error: &io::Error) -> bool {
+        error.get_ref().is_some_and(|inner| inner.is::<PasswordCancelled>())
+    }
+}
+
+impl fmt::Display for PasswordCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Password request for {} was cancelled", self.target)
+    }
+}
+
+impl std::error::Error for PasswordCancelled {}
+
+impl From<PasswordCancelled> for io::Error {
+    fn from(error: PasswordCancelled) -> Self {
+        io::Error::new(io::ErrorKind::PermissionDenied, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_output_hides_the_password() {
+        let password = Password::from("hunter2");
+        assert_eq!(password.as_bytes(), b"hunter2");
+        assert_eq!(format!("{:?}", password), "Password(..)");
+    }
+
+    #[test]
+    fn cancellation_is_recognised_through_io_errors() {
+        let error: io::Error = PasswordCancelled { target: "'a.txt'".to_string() }.into();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+        assert!(PasswordCancelled::is_cause_of(&error));
+        assert!(!PasswordCancelled::is_cause_of(&io::Error::other("other")));
+    }
+}
@@ -0,0 +1,33 @@
+// src/signature.rs
+
+/// The marker block that starts every RAR 5.0 archive.
+pub const RAR5_SIGNATURE: [u8; 8] = [0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00];
+
+/// The marker block that starts RAR 1.5 to 4.x archives.
+pub const RAR4_SIGNATURE: [u8; 7] = [0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x00];
+
+/// The archive format family identified by a signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RarFormat {
+    /// RAR 1.5 to 4.x.
+    Rar4,
+    /// RAR 5.0 and later.
+    Rar5,
+}
+
+impl RarFormat {
+    pub fn signature(&self) -> &'static [u8] {
+        match self {
+            RarFormat::Rar4 => &RAR4_SIGNATURE,
+            RarFormat::Rar5 => &RAR5_SIGNATURE,
+        }
+    }
+}
+
+/// Identifies the RAR format from the first bytes of a file, or returns `None`
+/// if they do not start with a RAR signature.
+pub fn detect_format(data: &[u8]) -> Option<RarFormat> {
+    [RarFormat::Rar5, RarFormat::Rar4]
+        .into_iter()
+        .find(|format| data.starts_with(format.signature()))
+}
@@ -1,8 +1,9 @@
 // src/structures/base.rs
 
 use crate::structures::{
-    end_of_archive_header::EndOfArchiveHeader, file_header::FileHeader,
-    main_archive_header::MainArchiveHeader,
+    encryption_header::EncryptionHeader, end_of_archive_header::EndOfArchiveHeader, file_header::FileHeader,
+    general_block_header::GeneralBlockHeader, main_archive_header::MainArchiveHeader,
+    service_header::ServiceHeader,
 };
 
 /// An enumeration of all possible block types in a RAR archive.
@@ -10,5 +11,16 @@ use crate::structures::{
 pub enum BlockHeader {
     Main(MainArchiveHeader),
     File(FileHeader),
+    Service(ServiceHeader),
+    Encryption(EncryptionHeader),
     End(EndOfArchiveHeader),
+    /// The end of a volume whose archive continues in the next volume.
+    VolumeEnd(EndOfArchiveHeader),
+    /// A block of a type this crate does not know, returned when its header
+    /// allows it to be skipped. `raw` holds the header bytes that follow the
+    /// general fields, including any extra area.
+    Unknown {
+        general_header: GeneralBlockHeader,
+        raw: Vec<u8>,
+    },
 }
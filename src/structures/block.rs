@@ -1,8 +1,9 @@
 // src/structures/block.rs
 
+use crate::structures::extra_area::{decode_extra_area, encode_extra_area, ExtraAreaRecord};
 use crate::structures::general_block_header::GeneralBlockHeader;
-use std::io::{self, Read, Write};
-use crate::encoder::write_vint;
+use std::io::{self, Cursor, Read, Write};
+use crate::encoder::frame_header;
 
 /// The `Block` trait defines a common interface for all RAR block types.
 /// It provides methods for encoding and decoding the block's specific data.
@@ -18,46 +19,51 @@ pub trait Block {
     fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()>;
 
     fn encode(&self, writer: &mut dyn Write) -> io::Result<()> {
-        let mut temp_buffer = Vec::new();
-        let temp_writer = &mut temp_buffer;
+        let base = self.get_base();
 
-        // Write header type and flags first
-        write_vint(temp_writer, self.header_type())?;
-        write_vint(temp_writer, self.get_base().general_header.header_flags)?;
+        // The specific data of the block, then its extra area
+        let mut body = Vec::new();
+        self.encode_data(&mut body)?;
+        let data_end = body.len();
+        encode_extra_area(&base.extra_area, &mut body)?;
+        let extra_area_size = (body.len() > data_end).then(|| (body.len() - data_end) as u64);
 
-        // Write data size if it exists
-        if let Some(data_size) = self.get_base().general_header.data_size {
-            write_vint(temp_writer, data_size)?;
-        }
-
-        // Write the specific data of the block
-        self.encode_data(temp_writer)?;
-
-        // Now we can calculate the header size
-        let header_size = temp_buffer.len() as u64;
-
-        // Write the CRC32 (dummy value for now), header size, and the rest of the header
-        writer.write_all(&[0,0,0,0])?; // Dummy CRC32
-        write_vint(writer, header_size)?;
-        writer.write_all(&temp_buffer)?;
-
-        Ok(())
+        frame_header(
+            writer,
+            self.header_type(),
+            base.general_header.header_flags,
+            extra_area_size,
+            base.general_header.data_size,
+            &body,
+        )
     }
 
     fn decode(general_header: GeneralBlockHeader, reader: &mut dyn Read) -> io::Result<Self>
     where
         Self: Sized + Default,
     {
-        let mut limited_reader = reader.take(general_header.header_size);
+        let mut header_bytes = Vec::new();
+        reader.take(general_header.header_size).read_to_end(&mut header_bytes)?;
+
+        // The extra area occupies the end of the header
+        let extra_area_size = general_header.extra_area_size.unwrap_or(0);
+        let body_size = usize::try_from(extra_area_size)
+            .ok()
+            .and_then(|size| header_bytes.len().checked_sub(size))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Extra area exceeds header size"))?;
+        let extra_area = decode_extra_area(&header_bytes[body_size..])?;
+
         let mut block = Self::default();
-        *block.get_mut_base() = BaseBlock { general_header };
-        block.decode_data(&mut limited_reader)?;
+        *block.get_mut_base() = BaseBlock { general_header, extra_area };
+        block.decode_data(&mut Cursor::new(&header_bytes[..body_size]))?;
         Ok(block)
     }
 }
 
-/// `BaseBlock` contains the `GeneralBlockHeader`, which is common to all block types.
+/// `BaseBlock` contains the `GeneralBlockHeader` and the extra area records,
+/// which are common to all block types.
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct BaseBlock {
     pub general_header: GeneralBlockHeader,
+    pub extra_area: Vec<ExtraAreaRecord>,
 }
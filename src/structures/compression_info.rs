@@ -0,0 +1,69 @@
+// src/structures/compression_info.rs
+
+/// Bits 0-5: the compression algorithm version.
+const VERSION_MASK: u64 = 0x003F;
+/// Bit 6: the entry continues the previous entry's solid stream.
+const SOLID_FLAG: u64 = 0x0040;
+/// Bits 15-19: RAR7 dictionary size fraction, in 1/32 steps of the base size.
+const FRACTION_SHIFT: u64 = 15;
+/// Bit 20: a RAR7 header whose data uses the RAR5 algorithm.
+const RAR5_COMPATIBLE_FLAG: u64 = 0x0010_0000;
+
+/// The smallest dictionary, used as the unit for the dictionary size exponent.
+const MIN_DICTIONARY_SIZE: u64 = 128 * 1024;
+
+/// RAR 5.0 compression algorithm.
+pub const VERSION_RAR5: u64 = 0;
+/// RAR 7.0 compression algorithm, which adds larger dictionaries and distances.
+pub const VERSION_RAR7: u64 = 1;
+
+/// The compression information vint of file and service headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionInfo(pub u64);
+
+impl CompressionInfo {
+    pub fn new(raw: u64) -> Self {
+        CompressionInfo(raw)
+    }
+
+    pub fn raw(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the compression algorithm version: 0 for RAR5, 1 for RAR7.
+    pub fn version(&self) -> u64 {
+        self.0 & VERSION_MASK
+    }
+
+    pub fn is_solid(&self) -> bool {
+        (self.0 & SOLID_FLAG) != 0
+    }
+
+    /// Returns the compression method, from 0 (stored) to 5 (best).
+    pub fn method(&self) -> u64 {
+        (self.0 >> 7) & 0x07
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.method() == 0
+    }
+
+    /// Returns the dictionary size in bytes.
+    ///
+    /// RAR5 uses a 4-bit exponent; RAR7 widens it to 5 bits and adds a 5-bit
+    /// fraction of 1/32 steps on top of the power of two.
+    pub fn dictionary_size(&self) -> u64 {
+        let exponent_mask = if self.version() == VERSION_RAR5 { 0x0F } else { 0x1F };
+        let base = MIN_DICTIONARY_SIZE << ((self.0 >> 10) & exponent_mask);
+        if self.version() == VERSION_RAR5 {
+            return base;
+        }
+        base + base / 32 * ((self.0 >> FRACTION_SHIFT) & 0x1F)
+    }
+
+    /// Returns true if the data uses the RAR7 distance alphabet. RAR7 headers
+    /// may flag their data as RAR5-compatible so it can continue a RAR5 solid stream.
+    pub fn uses_extended_distances(&self) -> bool {
+        self.version() == VERSION_RAR7 && (self.0 & RAR5_COMPATIBLE_FLAG) == 0
+    }
+}
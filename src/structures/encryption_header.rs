@@ -0,0 +1,84 @@
+// src/structures/encryption_header.rs
+
+use crate::crypto::{incorrect_password, Rar5Keys, CHECK_VALUE_SIZE, SALT_SIZE};
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use crate::structures::block::{Block, BaseBlock};
+use std::io::{self, Read, Write};
+
+/// Encryption flag: a password check value follows the salt.
+const PASSWORD_CHECK_FLAG: u64 = 0x0001;
+/// The only encryption version RAR5 defines: AES-256.
+pub const ENCRYPTION_VERSION_AES256: u64 = 0;
+
+/// The archive encryption header (type 4). It follows the signature in
+/// archives whose headers are encrypted; every block after it is encrypted.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EncryptionHeader {
+    pub base: BaseBlock,
+    pub version: u64,
+    /// Log2 of the PBKDF2 iteration count.
+    pub kdf_count: u8,
+    pub salt: [u8; SALT_SIZE],
+    pub check_value: Option<[u8; CHECK_VALUE_SIZE]>,
+}
+
+impl EncryptionHeader {
+    /// Derives the header keys from a password, rejecting it if it does not
+    /// match the stored check value.
+    pub fn derive_keys(&self, password: &[u8]) -> io::Result<Rar5Keys> {
+        let keys = Rar5Keys::derive(password, &self.salt, self.kdf_count)?;
+        match self.check_value {
+            Some(check_value) if !keys.matches_check_value(&check_value) => {
+                Err(incorrect_password("the archive headers"))
+            }
+            _ => Ok(keys),
+        }
+    }
+}
+
+impl Block for EncryptionHeader {
+    fn get_base(&self) -> &BaseBlock {
+        &self.base
+    }
+
+    fn get_mut_base(&mut self) -> &mut BaseBlock {
+        &mut self.base
+    }
+
+    fn header_type(&self) -> u64 {
+        4
+    }
+
+    fn encode_data(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_vint(writer, self.version)?;
+        write_vint(writer, if self.check_value.is_some() { PASSWORD_CHECK_FLAG } else { 0 })?;
+        writer.write_all(&[self.kdf_count])?;
+        writer.write_all(&self.salt)?;
+        if let Some(check_value) = &self.check_value {
+            writer.write_all(check_value)?;
+        }
+        Ok(())
+    }
+
+    fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()> {
+        self.version = read_vint(reader)?;
+        if self.version != ENCRYPTION_VERSION_AES256 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unsupported encryption version {}", self.version),
+            ));
+        }
+        let flags = read_vint(reader)?;
+        let mut kdf_count = [0u8; 1];
+        reader.read_exact(&mut kdf_count)?;
+        self.kdf_count = kdf_count[0];
+        reader.read_exact(&mut self.salt)?;
+        if flags & PASSWORD_CHECK_FLAG != 0 {
+            let mut check_value = [0u8; CHECK_VALUE_SIZE];
+            reader.read_exact(&mut check_value)?;
+            self.check_value = Some(check_value);
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,24 @@
 // src/structures/end_of_archive_header.rs
 
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
 use crate::structures::block::{Block, BaseBlock};
 use std::io::{self, Read, Write};
 
+/// End of archive flag: the archive continues in the next volume.
+const NOT_LAST_VOLUME_FLAG: u64 = 0x0001;
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct EndOfArchiveHeader {
     pub base: BaseBlock,
-    // Add End of Archive Header specific fields here
+    pub end_flags: u64,
+}
+
+impl EndOfArchiveHeader {
+    /// Returns true unless the archive continues in another volume.
+    pub fn is_last_volume(&self) -> bool {
+        (self.end_flags & NOT_LAST_VOLUME_FLAG) == 0
+    }
 }
 
 impl Block for EndOfArchiveHeader {
@@ -22,13 +34,12 @@ impl Block for EndOfArchiveHeader {
         5
     }
 
-    fn encode_data(&self, _writer: &mut dyn Write) -> io::Result<()> {
-        // No specific data to encode for EndOfArchiveHeader
-        Ok(())
+    fn encode_data(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write_vint(writer, self.end_flags)
     }
 
-    fn decode_data(&mut self, _reader: &mut dyn Read) -> io::Result<()> {
-        // No specific data to decode for EndOfArchiveHeader
+    fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()> {
+        self.end_flags = read_vint(reader)?;
         Ok(())
     }
 }
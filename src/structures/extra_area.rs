@@ -0,0 +1,79 @@
+// src/structures/extra_area.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor, Write};
+
+/// One record of a block's extra area, kept as raw bytes for the
+/// type-specific parsers to decode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtraAreaRecord {
+    pub kind: u64,
+    pub data: Vec<u8>,
+}
+
+/// Splits an extra area into its records.
+///
+/// Each record is a size vint, covering the type and data, followed by a type
+/// vint and the record data.
+pub fn decode_extra_area(extra_area: &[u8]) -> io::Result<Vec<ExtraAreaRecord>> {
+    let mut reader = Cursor::new(extra_area);
+    let mut records = Vec::new();
+    while (reader.position() as usize) < extra_area.len() {
+        let size = read_vint(&mut reader)?;
+        let record_start = reader.position();
+        let record_end = record_start
+            .checked_add(size)
+            .filter(|&end| end <= extra_area.len() as u64)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Extra record exceeds extra area"))?;
+        let kind = read_vint(&mut reader)?;
+        if reader.position() > record_end {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Extra record too short"));
+        }
+        let data = extra_area[reader.position() as usize..record_end as usize].to_vec();
+        reader.set_position(record_end);
+        records.push(ExtraAreaRecord { kind, data });
+    }
+    Ok(records)
+}
+
+/// Writes records in the extra area format read by `decode_extra_area`.
+pub fn encode_extra_area(records: &[ExtraAreaRecord], writer: &mut dyn Write) -> io::Result<()> {
+    for record in records {
+        let mut body = Vec::new();
+        write_vint(&mut body, record.kind)?;
+        body.extend_from_slice(&record.data);
+        write_vint(writer, body.len() as u64)?;
+        writer.write_all(&body)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_records_in_order() {
+        // A file time record (size 3, type 3) and an empty record of type 7.
+        let extra_area = [0x03, 0x03, 0x0A, 0xFF, 0x01, 0x07];
+        let records = decode_extra_area(&extra_area).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                ExtraAreaRecord { kind: 3, data: vec![0x0A, 0xFF] },
+                ExtraAreaRecord { kind: 7, data: vec![] },
+            ]
+        );
+
+        let mut encoded = Vec::new();
+        encode_extra_area(&records, &mut encoded).unwrap();
+        assert_eq!(encoded, extra_area);
+    }
+
+    #[test]
+    fn rejects_records_past_the_end() {
+        let error = decode_extra_area(&[0x05, 0x03, 0x00]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,142 @@
+// src/structures/extra_record.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use crate::structures::extra_area::ExtraAreaRecord;
+use crate::structures::file_encryption::FileEncryption;
+use crate::structures::file_hash::FileHash;
+use crate::structures::file_time::FileTimeRecord;
+use crate::structures::redirection::Redirection;
+use crate::structures::unix_owner::UnixOwner;
+use std::io::{self, Cursor};
+
+/// Extra record types used by file and service headers.
+pub const FILE_ENCRYPTION: u64 = 0x01;
+pub const FILE_HASH: u64 = 0x02;
+pub const FILE_TIME: u64 = 0x03;
+pub const FILE_VERSION: u64 = 0x04;
+pub const REDIRECTION: u64 = 0x05;
+pub const UNIX_OWNER: u64 = 0x06;
+pub const SERVICE_DATA: u64 = 0x07;
+
+/// A decoded extra record of a file or service header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtraRecord {
+    /// The AES-256 parameters of an encrypted entry.
+    FileEncryption(FileEncryption),
+    /// A hash of the unpacked data.
+    FileHash(FileHash),
+    /// High-precision modification, creation and access times.
+    FileTime(FileTimeRecord),
+    /// A symlink, junction, hard link or file copy.
+    Redirection(Redirection),
+    /// The user and group that own the file on Unix.
+    UnixOwner(UnixOwner),
+    /// The file version number, for archives made with file versioning.
+    FileVersion { flags: u64, version: u64 },
+    /// Data belonging to a service header, kept as raw bytes.
+    ServiceData(Vec<u8>),
+    /// A record this crate does not decode, or a known record that is
+    /// malformed, kept as raw bytes.
+    Unknown { kind: u64, data: Vec<u8> },
+}
+
+impl ExtraRecord {
+    /// Decodes a raw record. Unrecognised types become `ExtraRecord::Unknown`.
+    pub fn decode(record: &ExtraAreaRecord) -> io::Result<Self> {
+        let mut reader = Cursor::new(record.data.as_slice());
+        let decoded = match record.kind {
+            FILE_ENCRYPTION => ExtraRecord::FileEncryption(FileEncryption::decode(&record.data)?),
+            FILE_HASH => ExtraRecord::FileHash(FileHash::decode(&record.data)?),
+            FILE_TIME => ExtraRecord::FileTime(FileTimeRecord::decode(&record.data)?),
+            REDIRECTION => ExtraRecord::Redirection(Redirection::decode(&record.data)?),
+            UNIX_OWNER => ExtraRecord::UnixOwner(UnixOwner::decode(&record.data)?),
+            FILE_VERSION => ExtraRecord::FileVersion {
+                flags: read_vint(&mut reader)?,
+                version: read_vint(&mut reader)?,
+            },
+            SERVICE_DATA => ExtraRecord::ServiceData(record.data.clone()),
+            kind => {
+                return Ok(ExtraRecord::Unknown {
+                    kind,
+                    data: record.data.clone(),
+                })
+            }
+        };
+        Ok(decoded)
+    }
+
+    /// Encodes the record back into its raw form.
+    pub fn encode(&self) -> io::Result<ExtraAreaRecord> {
+        let mut data = Vec::new();
+        let kind = match self {
+            ExtraRecord::FileEncryption(encryption) => {
+                data = encryption.encode()?;
+                FILE_ENCRYPTION
+            }
+            ExtraRecord::FileHash(file_hash) => {
+                data = file_hash.encode()?;
+                FILE_HASH
+            }
+            ExtraRecord::FileTime(file_time) => {
+                data = file_time.encode()?;
+                FILE_TIME
+            }
+            ExtraRecord::Redirection(redirection) => {
+                data = redirection.encode()?;
+                REDIRECTION
+            }
+            ExtraRecord::UnixOwner(owner) => {
+                data = owner.encode()?;
+                UNIX_OWNER
+            }
+            ExtraRecord::FileVersion { flags, version } => {
+                write_vint(&mut data, *flags)?;
+                write_vint(&mut data, *version)?;
+                FILE_VERSION
+            }
+            ExtraRecord::ServiceData(raw) => {
+                data.extend_from_slice(raw);
+                SERVICE_DATA
+            }
+            ExtraRecord::Unknown { kind, data: raw } => {
+                data.extend_from_slice(raw);
+                *kind
+            }
+        };
+        Ok(ExtraAreaRecord { kind, data })
+    }
+
+    /// Returns the record type.
+    pub fn kind(&self) -> u64 {
+        match self {
+            ExtraRecord::FileEncryption(_) => FILE_ENCRYPTION,
+            ExtraRecord::FileHash(_) => FILE_HASH,
+            ExtraRecord::FileTime(_) => FILE_TIME,
+            ExtraRecord::Redirection(_) => REDIRECTION,
+            ExtraRecord::UnixOwner(_) => UNIX_OWNER,
+            ExtraRecord::FileVersion { .. } => FILE_VERSION,
+            ExtraRecord::ServiceData(_) => SERVICE_DATA,
+            ExtraRecord::Unknown { kind, .. } => *kind,
+        }
+    }
+}
+
+/// Decodes every record of an extra area. A malformed record is kept as
+/// `ExtraRecord::Unknown` so it does not hide the others.
+pub fn decode_extra_records(records: &[ExtraAreaRecord]) -> Vec<ExtraRecord> {
+    records
+        .iter()
+        .map(|record| {
+            ExtraRecord::decode(record).unwrap_or_else(|_| ExtraRecord::Unknown {
+                kind: record.kind,
+                data: record.data.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Encodes typed records into the raw form stored on `BaseBlock`.
+pub fn encode_extra_records(records: &[ExtraRecord]) -> io::Result<Vec<ExtraAreaRecord>> {
+    records.iter().map(ExtraRecord::encode).collect()
+}
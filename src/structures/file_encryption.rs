@@ -0,0 +1,85 @@
+// src/structures/file_encryption.rs
+
+use crate::crypto::{incorrect_password, Rar5Keys, CHECK_VALUE_SIZE, IV_SIZE, SALT_SIZE};
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use crate::structures::encryption_header::ENCRYPTION_VERSION_AES256;
+use std::io::{self, Cursor, Read, Write};
+
+/// Record flag: a password check value follows the IV.
+const PASSWORD_CHECK_FLAG: u64 = 0x0001;
+/// Record flag: stored checksums are MACs keyed by the password.
+const USE_MAC_FLAG: u64 = 0x0002;
+
+/// The file encryption extra record (type 0x01) of an encrypted entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileEncryption {
+    pub version: u64,
+    /// The data CRC32 and hash are stored as MACs rather than plain checksums.
+    pub use_mac: bool,
+    /// Log2 of the PBKDF2 iteration count.
+    pub kdf_count: u8,
+    pub salt: [u8; SALT_SIZE],
+    pub iv: [u8; IV_SIZE],
+    pub check_value: Option<[u8; CHECK_VALUE_SIZE]>,
+}
+
+impl FileEncryption {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let version = read_vint(&mut reader)?;
+        if version != ENCRYPTION_VERSION_AES256 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unsupported encryption version {}", version),
+            ));
+        }
+        let flags = read_vint(&mut reader)?;
+        let mut record = FileEncryption {
+            version,
+            use_mac: flags & USE_MAC_FLAG != 0,
+            ..Default::default()
+        };
+        let mut kdf_count = [0u8; 1];
+        reader.read_exact(&mut kdf_count)?;
+        record.kdf_count = kdf_count[0];
+        reader.read_exact(&mut record.salt)?;
+        reader.read_exact(&mut record.iv)?;
+        if flags & PASSWORD_CHECK_FLAG != 0 {
+            let mut check_value = [0u8; CHECK_VALUE_SIZE];
+            reader.read_exact(&mut check_value)?;
+            record.check_value = Some(check_value);
+        }
+        Ok(record)
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut flags = 0;
+        if self.check_value.is_some() {
+            flags |= PASSWORD_CHECK_FLAG;
+        }
+        if self.use_mac {
+            flags |= USE_MAC_FLAG;
+        }
+        let mut data = Vec::new();
+        write_vint(&mut data, self.version)?;
+        write_vint(&mut data, flags)?;
+        data.write_all(&[self.kdf_count])?;
+        data.write_all(&self.salt)?;
+        data.write_all(&self.iv)?;
+        if let Some(check_value) = &self.check_value {
+            data.write_all(check_value)?;
+        }
+        Ok(data)
+    }
+
+    /// Checks derived keys against the stored password check value, if any.
+    pub fn check_password(&self, keys: &Rar5Keys, name: &str) -> io::Result<()> {
+        match self.check_value {
+            Some(check_value) if !keys.matches_check_value(&check_value) => {
+                Err(incorrect_password(&format!("'{}'", name)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// src/structures/file_hash.rs
+
+use crate::blake2sp::DIGEST_SIZE;
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor, Read};
+
+/// Hash type of a BLAKE2sp digest.
+const HASH_BLAKE2SP: u64 = 0;
+
+/// The file hash extra record (type 0x02).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileHash {
+    /// BLAKE2sp digest of the unpacked data.
+    Blake2sp([u8; DIGEST_SIZE]),
+    /// A hash type this crate does not know, kept as raw bytes.
+    Unknown { hash_type: u64, data: Vec<u8> },
+}
+
+impl FileHash {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let hash_type = read_vint(&mut reader)?;
+        if hash_type == HASH_BLAKE2SP {
+            let mut digest = [0u8; DIGEST_SIZE];
+            reader.read_exact(&mut digest)?;
+            return Ok(FileHash::Blake2sp(digest));
+        }
+        let mut hash_data = Vec::new();
+        reader.read_to_end(&mut hash_data)?;
+        Ok(FileHash::Unknown { hash_type, data: hash_data })
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        match self {
+            FileHash::Blake2sp(digest) => {
+                write_vint(&mut data, HASH_BLAKE2SP)?;
+                data.extend_from_slice(digest);
+            }
+            FileHash::Unknown { hash_type, data: hash_data } => {
+                write_vint(&mut data, *hash_type)?;
+                data.extend_from_slice(hash_data);
+            }
+        }
+        Ok(data)
+    }
+}
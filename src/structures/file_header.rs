@@ -3,16 +3,44 @@
 use crate::decoder::read_vint;
 use crate::encoder::write_vint;
 use crate::structures::block::{Block, BaseBlock};
+use crate::structures::compression_info::CompressionInfo;
+use crate::structures::extra_record::{
+    decode_extra_records, encode_extra_records, ExtraRecord, FILE_ENCRYPTION, FILE_HASH, FILE_TIME, REDIRECTION,
+    UNIX_OWNER,
+};
+use crate::structures::file_encryption::FileEncryption;
+use crate::structures::file_hash::FileHash;
+use crate::structures::file_time::FileTimeRecord;
+use crate::structures::redirection::Redirection;
+use crate::structures::unix_owner::UnixOwner;
 use crate::decompression::{DecompressionError, self};
+use log::debug;
 use std::io::{self, Read, Write};
 
-#[derive(Debug, Default, PartialEq, Eq)]
+/// File flag: the entry is a directory.
+const DIRECTORY_FLAG: u64 = 0x0001;
+/// File flag: a Unix modification time follows the attributes.
+const MTIME_FLAG: u64 = 0x0002;
+/// File flag: a CRC32 of the unpacked data follows the modification time.
+const DATA_CRC32_FLAG: u64 = 0x0004;
+/// File flag: the unpacked size is unknown and the stored value must be ignored.
+const UNKNOWN_SIZE_FLAG: u64 = 0x0008;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct FileHeader {
     pub base: BaseBlock,
-    pub compression_method: u64,
-    pub os_type: u64,
-    pub file_crc32: u32,
-    pub file_time: u64,
+    /// File flags. The modification time, data CRC32 and unknown size bits are
+    /// derived from the optional fields below when encoding.
+    pub file_flags: u64,
+    /// Unpacked size, or `None` if the archiver did not know it.
+    pub unpacked_size: Option<u64>,
+    pub attributes: u64,
+    /// Modification time in Unix format.
+    pub mtime: Option<u32>,
+    /// CRC32 of the unpacked data.
+    pub data_crc32: Option<u32>,
+    pub compression_info: CompressionInfo,
+    pub host_os: u64,
     pub file_name: String,
 }
 
@@ -30,42 +58,155 @@ impl Block for FileHeader {
     }
 
     fn encode_data(&self, writer: &mut dyn Write) -> io::Result<()> {
-        println!("[FileHeader::encode_data] Encoding file header for '{}'", self.file_name);
-        write_vint(writer, self.compression_method)?;
-        write_vint(writer, self.os_type)?;
-        writer.write_all(&self.file_crc32.to_le_bytes())?;
-        write_vint(writer, self.file_time)?;
+        debug!("[FileHeader::encode_data] Encoding file header for '{}'", self.file_name);
+        write_vint(writer, self.encoded_file_flags())?;
+        write_vint(writer, self.unpacked_size.unwrap_or(0))?;
+        write_vint(writer, self.attributes)?;
+        if let Some(mtime) = self.mtime {
+            writer.write_all(&mtime.to_le_bytes())?;
+        }
+        if let Some(data_crc32) = self.data_crc32 {
+            writer.write_all(&data_crc32.to_le_bytes())?;
+        }
+        write_vint(writer, self.compression_info.raw())?;
+        write_vint(writer, self.host_os)?;
         write_vint(writer, self.file_name.len() as u64)?;
         writer.write_all(self.file_name.as_bytes())?;
         Ok(())
     }
 
     fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()> {
-        println!("[FileHeader::decode_data] Decoding file header...");
-        self.compression_method = read_vint(reader)?;
-        println!("[FileHeader::decode_data] Compression method: {}", self.compression_method);
-        self.os_type = read_vint(reader)?;
-        println!("[FileHeader::decode_data] OS type: {}", self.os_type);
-        let mut crc32_bytes = [0u8; 4];
-        reader.read_exact(&mut crc32_bytes)?;
-        self.file_crc32 = u32::from_le_bytes(crc32_bytes);
-        println!("[FileHeader::decode_data] File CRC32: {:#010x}", self.file_crc32);
-        self.file_time = read_vint(reader)?;
-        println!("[FileHeader::decode_data] File time: {}", self.file_time);
-        let file_name_len = read_vint(reader)? as usize;
-        println!("[FileHeader::decode_data] File name length: {}", file_name_len);
-        let mut file_name_bytes = vec![0u8; file_name_len];
-        reader.read_exact(&mut file_name_bytes)?;
+        debug!("[FileHeader::decode_data] Decoding file header...");
+        self.file_flags = read_vint(reader)?;
+        debug!("[FileHeader::decode_data] File flags: {:#06x}", self.file_flags);
+        let unpacked_size = read_vint(reader)?;
+        self.unpacked_size = (self.file_flags & UNKNOWN_SIZE_FLAG == 0).then_some(unpacked_size);
+        debug!("[FileHeader::decode_data] Unpacked size: {:?}", self.unpacked_size);
+        self.attributes = read_vint(reader)?;
+        debug!("[FileHeader::decode_data] Attributes: {:#x}", self.attributes);
+        if self.file_flags & MTIME_FLAG != 0 {
+            self.mtime = Some(read_u32(reader)?);
+        }
+        debug!("[FileHeader::decode_data] Modification time: {:?}", self.mtime);
+        if self.file_flags & DATA_CRC32_FLAG != 0 {
+            self.data_crc32 = Some(read_u32(reader)?);
+        }
+        debug!("[FileHeader::decode_data] Data CRC32: {:x?}", self.data_crc32);
+        self.compression_info = CompressionInfo::new(read_vint(reader)?);
+        debug!("[FileHeader::decode_data] Compression info: {:?}", self.compression_info);
+        self.host_os = read_vint(reader)?;
+        debug!("[FileHeader::decode_data] Host OS: {}", self.host_os);
+        let file_name_len = read_vint(reader)?;
+        debug!("[FileHeader::decode_data] File name length: {}", file_name_len);
+        // Read no more than the header holds rather than allocating the
+        // stored length up front.
+        let mut file_name_bytes = Vec::new();
+        reader.take(file_name_len).read_to_end(&mut file_name_bytes)?;
+        if file_name_bytes.len() as u64 != file_name_len {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "File name exceeds header"));
+        }
         self.file_name = String::from_utf8(file_name_bytes)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        println!("[FileHeader::decode_data] File name: {}", self.file_name);
+        debug!("[FileHeader::decode_data] File name: {}", self.file_name);
         Ok(())
     }
 }
 
+fn read_u32(reader: &mut dyn Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
 impl FileHeader {
+    pub fn is_directory(&self) -> bool {
+        (self.file_flags & DIRECTORY_FLAG) != 0
+    }
+
+    /// Returns true if the entry's data must be decoded with the dictionary left
+    /// by the previous entry.
+    pub fn is_solid(&self) -> bool {
+        self.compression_info.is_solid()
+    }
+
+    /// Decodes the records of the header's extra area. Malformed records are
+    /// returned as `ExtraRecord::Unknown`; the typed accessors report them.
+    pub fn extra_records(&self) -> Vec<ExtraRecord> {
+        decode_extra_records(&self.base.extra_area)
+    }
+
+    /// Decodes the first record of type `kind` only, so a malformed or newer
+    /// record of another type does not hide it.
+    fn find_record<T>(&self, kind: u64, typed: impl FnOnce(ExtraRecord) -> Option<T>) -> io::Result<Option<T>> {
+        match self.base.extra_area.iter().find(|record| record.kind == kind) {
+            Some(record) => Ok(typed(ExtraRecord::decode(record)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the encryption record if the entry's data is encrypted.
+    pub fn encryption(&self) -> io::Result<Option<FileEncryption>> {
+        self.find_record(FILE_ENCRYPTION, |record| match record {
+            ExtraRecord::FileEncryption(encryption) => Some(encryption),
+            _ => None,
+        })
+    }
+
+    /// Returns the hash of the unpacked data, if the header has one.
+    pub fn file_hash(&self) -> io::Result<Option<FileHash>> {
+        self.find_record(FILE_HASH, |record| match record {
+            ExtraRecord::FileHash(file_hash) => Some(file_hash),
+            _ => None,
+        })
+    }
+
+    /// Returns the file time record, if the header has one.
+    pub fn file_time(&self) -> io::Result<Option<FileTimeRecord>> {
+        self.find_record(FILE_TIME, |record| match record {
+            ExtraRecord::FileTime(file_time) => Some(file_time),
+            _ => None,
+        })
+    }
+
+    /// Returns the redirection record if the entry is a link or file copy.
+    pub fn redirection(&self) -> io::Result<Option<Redirection>> {
+        self.find_record(REDIRECTION, |record| match record {
+            ExtraRecord::Redirection(redirection) => Some(redirection),
+            _ => None,
+        })
+    }
+
+    /// Returns the Unix owner record, if the archiver stored one.
+    pub fn unix_owner(&self) -> io::Result<Option<UnixOwner>> {
+        self.find_record(UNIX_OWNER, |record| match record {
+            ExtraRecord::UnixOwner(owner) => Some(owner),
+            _ => None,
+        })
+    }
+
+    /// Replaces the header's extra area; `Block::encode` writes it back out.
+    pub fn set_extra_records(&mut self, records: &[ExtraRecord]) -> io::Result<()> {
+        self.base.extra_area = encode_extra_records(records)?;
+        Ok(())
+    }
+
+    /// Returns the file flags with the optional-field bits matching the fields.
+    fn encoded_file_flags(&self) -> u64 {
+        let mut flags = self.file_flags & !(MTIME_FLAG | DATA_CRC32_FLAG | UNKNOWN_SIZE_FLAG);
+        if self.mtime.is_some() {
+            flags |= MTIME_FLAG;
+        }
+        if self.data_crc32.is_some() {
+            flags |= DATA_CRC32_FLAG;
+        }
+        if self.unpacked_size.is_none() {
+            flags |= UNKNOWN_SIZE_FLAG;
+        }
+        flags
+    }
+
     pub fn decompress(&self, compressed_data: &[u8]) -> Result<Vec<u8>, DecompressionError> {
-        println!("[FileHeader::decompress] Decompressing file data...");
-        decompression::decompress_data(compressed_data, self.compression_method)
+        debug!("[FileHeader::decompress] Decompressing file data...");
+        decompression::decompress_data(compressed_data, self.compression_info)
     }
 }
@@ -0,0 +1,151 @@
+// src/structures/file_time.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor, Read};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Record flag: times are stored as Unix time rather than Windows FILETIME.
+const UNIX_TIME_FLAG: u64 = 0x0001;
+const MTIME_FLAG: u64 = 0x0002;
+const CTIME_FLAG: u64 = 0x0004;
+const ATIME_FLAG: u64 = 0x0008;
+/// Record flag: each Unix time is followed by a nanosecond part.
+const UNIX_NANOSECONDS_FLAG: u64 = 0x0010;
+/// The bits of a nanosecond part that hold the value; the top two are reserved.
+const NANOSECONDS_MASK: u32 = 0x3FFF_FFFF;
+
+/// Seconds between 1601-01-01, the FILETIME epoch, and the Unix epoch.
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+const FILETIME_TICKS_PER_SECOND: u64 = 10_000_000;
+
+/// How the times of a file time record are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// 100 ns ticks since 1601, as written by WinRAR on Windows.
+    #[default]
+    WindowsFileTime,
+    /// Whole seconds since the Unix epoch.
+    Unix,
+    /// Seconds since the Unix epoch plus a nanosecond part.
+    UnixNanoseconds,
+}
+
+/// The high-precision times of the file time extra record (type 0x03).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FileTimeRecord {
+    pub format: TimeFormat,
+    pub mtime: Option<SystemTime>,
+    pub ctime: Option<SystemTime>,
+    pub atime: Option<SystemTime>,
+}
+
+impl FileTimeRecord {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let flags = read_vint(&mut reader)?;
+        let format = match (flags & UNIX_TIME_FLAG != 0, flags & UNIX_NANOSECONDS_FLAG != 0) {
+            (false, _) => TimeFormat::WindowsFileTime,
+            (true, false) => TimeFormat::Unix,
+            (true, true) => TimeFormat::UnixNanoseconds,
+        };
+
+        let mut times = [None; 3];
+        for (time, flag) in times.iter_mut().zip([MTIME_FLAG, CTIME_FLAG, ATIME_FLAG]) {
+            if flags & flag == 0 {
+                continue;
+            }
+            *time = Some(match format {
+                TimeFormat::WindowsFileTime => from_filetime(read_u64(&mut reader)?),
+                _ => UNIX_EPOCH + Duration::from_secs(read_u32(&mut reader)? as u64),
+            });
+        }
+        // The nanosecond parts follow all of the seconds, in the same order.
+        // Reserved bits are masked and out of range parts dropped, keeping the
+        // whole seconds, as unrar does.
+        if format == TimeFormat::UnixNanoseconds {
+            for time in times.iter_mut().flatten() {
+                let nanoseconds = read_u32(&mut reader)? & NANOSECONDS_MASK;
+                if nanoseconds < 1_000_000_000 {
+                    *time += Duration::from_nanos(nanoseconds as u64);
+                }
+            }
+        }
+
+        let [mtime, ctime, atime] = times;
+        Ok(FileTimeRecord { format, mtime, ctime, atime })
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let times = [self.mtime, self.ctime, self.atime];
+        let mut flags = match self.format {
+            TimeFormat::WindowsFileTime => 0,
+            TimeFormat::Unix => UNIX_TIME_FLAG,
+            TimeFormat::UnixNanoseconds => UNIX_TIME_FLAG | UNIX_NANOSECONDS_FLAG,
+        };
+        for (time, flag) in times.iter().zip([MTIME_FLAG, CTIME_FLAG, ATIME_FLAG]) {
+            if time.is_some() {
+                flags |= flag;
+            }
+        }
+
+        let mut data = Vec::new();
+        write_vint(&mut data, flags)?;
+        for &time in times.iter().flatten() {
+            match self.format {
+                TimeFormat::WindowsFileTime => data.extend_from_slice(&to_filetime(time)?.to_le_bytes()),
+                _ => data.extend_from_slice(&(unix_duration(time)?.as_secs() as u32).to_le_bytes()),
+            }
+        }
+        if self.format == TimeFormat::UnixNanoseconds {
+            for &time in times.iter().flatten() {
+                data.extend_from_slice(&unix_duration(time)?.subsec_nanos().to_le_bytes());
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn from_filetime(ticks: u64) -> SystemTime {
+    let since_1601 = Duration::from_secs(ticks / FILETIME_TICKS_PER_SECOND)
+        + Duration::from_nanos((ticks % FILETIME_TICKS_PER_SECOND) * 100);
+    let offset = Duration::from_secs(FILETIME_UNIX_OFFSET);
+    if since_1601 >= offset {
+        UNIX_EPOCH + (since_1601 - offset)
+    } else {
+        UNIX_EPOCH - (offset - since_1601)
+    }
+}
+
+fn to_filetime(time: SystemTime) -> io::Result<u64> {
+    let since_1601 = match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => Duration::from_secs(FILETIME_UNIX_OFFSET) + after,
+        Err(before) => Duration::from_secs(FILETIME_UNIX_OFFSET)
+            .checked_sub(before.duration())
+            .ok_or_else(time_out_of_range)?,
+    };
+    u64::try_from(since_1601.as_nanos() / 100).map_err(|_| time_out_of_range())
+}
+
+fn unix_duration(time: SystemTime) -> io::Result<Duration> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .filter(|duration| duration.as_secs() <= u32::MAX as u64)
+        .ok_or_else(time_out_of_range)
+}
+
+fn time_out_of_range() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "Time cannot be stored in this format")
+}
+
+fn read_u32(reader: &mut dyn Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut dyn Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
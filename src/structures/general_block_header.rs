@@ -1,11 +1,74 @@
 // src/structures/general_block_header.rs
 
+/// The header flags common to all block types.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct HeaderFlags(pub u64);
+
+impl HeaderFlags {
+    /// An extra area follows the type-specific fields.
+    pub const EXTRA_AREA: u64 = 0x0001;
+    /// A data area follows the header.
+    pub const DATA_AREA: u64 = 0x0002;
+    /// Readers that do not know the block type may skip it.
+    pub const SKIP_IF_UNKNOWN: u64 = 0x0004;
+    /// The data area continues from the previous volume.
+    pub const SPLIT_BEFORE: u64 = 0x0008;
+    /// The data area continues in the next volume.
+    pub const SPLIT_AFTER: u64 = 0x0010;
+    /// The block depends on the preceding file block.
+    pub const CHILD: u64 = 0x0020;
+    /// Keep this child block if its parent is modified.
+    pub const INHERITED: u64 = 0x0040;
+
+    pub fn contains(&self, flag: u64) -> bool {
+        (self.0 & flag) != 0
+    }
+
+    /// Returns a copy with `flag` set or cleared.
+    pub fn with(self, flag: u64, enabled: bool) -> Self {
+        if enabled {
+            HeaderFlags(self.0 | flag)
+        } else {
+            HeaderFlags(self.0 & !flag)
+        }
+    }
+
+    pub fn has_extra_area(&self) -> bool {
+        self.contains(Self::EXTRA_AREA)
+    }
+
+    pub fn has_data_area(&self) -> bool {
+        self.contains(Self::DATA_AREA)
+    }
+
+    pub fn skip_if_unknown(&self) -> bool {
+        self.contains(Self::SKIP_IF_UNKNOWN)
+    }
+
+    pub fn is_split_before(&self) -> bool {
+        self.contains(Self::SPLIT_BEFORE)
+    }
+
+    pub fn is_split_after(&self) -> bool {
+        self.contains(Self::SPLIT_AFTER)
+    }
+}
+
 /// Represents the general header found at the beginning of most RAR blocks.
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct GeneralBlockHeader {
     pub crc32: u32,          // Checksum of the block header
     pub header_size: u64,    // Total size of the block header (variable-length integer)
     pub header_type: u64,    // Type of the block (variable-length integer)
-    pub header_flags: u64,   // Bitmask of flags (variable-length integer)
+    pub header_flags: HeaderFlags, // Bitmask of flags (variable-length integer)
+    pub extra_area_size: Option<u64>, // Size of the extra area at the end of the header (optional, variable-length integer)
     pub data_size: Option<u64>, // Size of the data area following the header (optional, variable-length integer)
+    pub computed_crc32: Option<u32>, // CRC32 computed over the header bytes, if they were checked
+}
+
+impl GeneralBlockHeader {
+    /// Returns true if the header was checked and its stored CRC32 does not match.
+    pub fn has_crc_mismatch(&self) -> bool {
+        matches!(self.computed_crc32, Some(computed) if computed != self.crc32)
+    }
 }
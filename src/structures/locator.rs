@@ -0,0 +1,56 @@
+// src/structures/locator.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor};
+
+/// Main header extra record type of the locator.
+pub const LOCATOR: u64 = 0x01;
+
+/// Record flag: the quick open offset is present.
+const QUICK_OPEN_FLAG: u64 = 0x0001;
+/// Record flag: the recovery record offset is present.
+const RECOVERY_FLAG: u64 = 0x0002;
+
+/// The locator extra record (type 0x01) of the main archive header.
+///
+/// Offsets are measured from the start of the main archive header block. An
+/// offset of zero means the archiver reserved the field but left it unset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Locator {
+    /// Where the quick open service block starts.
+    pub quick_open_offset: Option<u64>,
+    /// Where the recovery record service block starts.
+    pub recovery_offset: Option<u64>,
+}
+
+impl Locator {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let flags = read_vint(&mut reader)?;
+        let mut locator = Locator::default();
+        if flags & QUICK_OPEN_FLAG != 0 {
+            locator.quick_open_offset = Some(read_vint(&mut reader)?);
+        }
+        if flags & RECOVERY_FLAG != 0 {
+            locator.recovery_offset = Some(read_vint(&mut reader)?);
+        }
+        Ok(locator)
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut flags = 0;
+        if self.quick_open_offset.is_some() {
+            flags |= QUICK_OPEN_FLAG;
+        }
+        if self.recovery_offset.is_some() {
+            flags |= RECOVERY_FLAG;
+        }
+        let mut data = Vec::new();
+        write_vint(&mut data, flags)?;
+        for offset in [self.quick_open_offset, self.recovery_offset].into_iter().flatten() {
+            write_vint(&mut data, offset)?;
+        }
+        Ok(data)
+    }
+}
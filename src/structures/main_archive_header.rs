@@ -1,12 +1,78 @@
 // src/structures/main_archive_header.rs
 
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
 use crate::structures::block::{Block, BaseBlock};
+use crate::structures::extra_area::ExtraAreaRecord;
+use crate::structures::locator::{Locator, LOCATOR};
 use std::io::{self, Write, Read};
 
+/// Archive flag: the archive is one volume of a multi-volume set.
+const VOLUME_FLAG: u64 = 0x0001;
+/// Archive flag: the volume number field is present.
+const VOLUME_NUMBER_FLAG: u64 = 0x0002;
+/// Archive flag: the archive is solid.
+const SOLID_FLAG: u64 = 0x0004;
+/// Archive flag: a recovery record is present.
+const RECOVERY_RECORD_FLAG: u64 = 0x0008;
+/// Archive flag: the archive is locked against changes.
+const LOCKED_FLAG: u64 = 0x0010;
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct MainArchiveHeader {
     pub base: BaseBlock,
-    // Add Main Archive Header specific fields here
+    pub archive_flags: u64,
+    /// The volume's position in a multi-volume set. RAR omits it for the
+    /// first volume, which is number 0.
+    pub volume_number: Option<u64>,
+}
+
+impl MainArchiveHeader {
+    /// Returns true if the archive is one volume of a multi-volume set.
+    pub fn is_volume(&self) -> bool {
+        (self.archive_flags & VOLUME_FLAG) != 0
+    }
+
+    /// Returns true if the archive's entries form one solid stream.
+    pub fn is_solid(&self) -> bool {
+        (self.archive_flags & SOLID_FLAG) != 0
+    }
+
+    /// Returns true if the archive carries a recovery record.
+    pub fn has_recovery_record(&self) -> bool {
+        (self.archive_flags & RECOVERY_RECORD_FLAG) != 0
+    }
+
+    /// Returns true if the archive is locked against changes.
+    pub fn is_locked(&self) -> bool {
+        (self.archive_flags & LOCKED_FLAG) != 0
+    }
+
+    /// Returns the locator record, if the header has one.
+    pub fn locator(&self) -> io::Result<Option<Locator>> {
+        self.base
+            .extra_area
+            .iter()
+            .find(|record| record.kind == LOCATOR)
+            .map(|record| Locator::decode(&record.data))
+            .transpose()
+    }
+
+    /// Replaces or removes the locator record, keeping any other records of
+    /// the extra area.
+    pub fn set_locator(&mut self, locator: Option<Locator>) -> io::Result<()> {
+        self.base.extra_area.retain(|record| record.kind != LOCATOR);
+        if let Some(locator) = locator {
+            self.base.extra_area.insert(
+                0,
+                ExtraAreaRecord {
+                    kind: LOCATOR,
+                    data: locator.encode()?,
+                },
+            );
+        }
+        Ok(())
+    }
 }
 
 impl Block for MainArchiveHeader {
@@ -22,13 +88,23 @@ impl Block for MainArchiveHeader {
         1
     }
 
-    fn encode_data(&self, _writer: &mut dyn Write) -> io::Result<()> {
-        // No specific data to encode for MainArchiveHeader
+    fn encode_data(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let mut archive_flags = self.archive_flags & !VOLUME_NUMBER_FLAG;
+        if self.volume_number.is_some() {
+            archive_flags |= VOLUME_NUMBER_FLAG;
+        }
+        write_vint(writer, archive_flags)?;
+        if let Some(volume_number) = self.volume_number {
+            write_vint(writer, volume_number)?;
+        }
         Ok(())
     }
 
-    fn decode_data(&mut self, _reader: &mut dyn Read) -> io::Result<()> {
-        // No specific data to decode for MainArchiveHeader
+    fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()> {
+        self.archive_flags = read_vint(reader)?;
+        if self.archive_flags & VOLUME_NUMBER_FLAG != 0 {
+            self.volume_number = Some(read_vint(reader)?);
+        }
         Ok(())
     }
 }
@@ -2,9 +2,20 @@
 
 pub mod base;
 pub mod block;
+pub mod compression_info;
+pub mod encryption_header;
 pub mod end_of_archive_header;
+pub mod extra_area;
+pub mod extra_record;
+pub mod file_encryption;
+pub mod file_hash;
 pub mod file_header;
+pub mod file_time;
 pub mod general_block_header;
+pub mod locator;
 pub mod main_archive_header;
+pub mod redirection;
+pub mod service_header;
+pub mod unix_owner;
 
 pub use base::BlockHeader;
@@ -0,0 +1,86 @@
+// src/structures/redirection.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor, Read};
+
+/// Redirection flag: the link target is a directory.
+const DIRECTORY_FLAG: u64 = 0x0001;
+
+/// The kind of link an entry stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectionKind {
+    UnixSymlink,
+    WindowsSymlink,
+    WindowsJunction,
+    HardLink,
+    /// The entry's data is a copy of an earlier entry.
+    FileCopy,
+    /// A redirection type this crate does not know.
+    Other(u64),
+}
+
+impl RedirectionKind {
+    pub fn from_raw(value: u64) -> Self {
+        match value {
+            1 => RedirectionKind::UnixSymlink,
+            2 => RedirectionKind::WindowsSymlink,
+            3 => RedirectionKind::WindowsJunction,
+            4 => RedirectionKind::HardLink,
+            5 => RedirectionKind::FileCopy,
+            other => RedirectionKind::Other(other),
+        }
+    }
+
+    pub fn raw(&self) -> u64 {
+        match *self {
+            RedirectionKind::UnixSymlink => 1,
+            RedirectionKind::WindowsSymlink => 2,
+            RedirectionKind::WindowsJunction => 3,
+            RedirectionKind::HardLink => 4,
+            RedirectionKind::FileCopy => 5,
+            RedirectionKind::Other(other) => other,
+        }
+    }
+}
+
+/// The redirection extra record (type 0x05).
+///
+/// For hard links and file copies `target` names an earlier entry of the
+/// archive; for symlinks and junctions it is the link text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirection {
+    pub kind: RedirectionKind,
+    pub target: String,
+    pub is_directory: bool,
+}
+
+impl Redirection {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let kind = RedirectionKind::from_raw(read_vint(&mut reader)?);
+        let flags = read_vint(&mut reader)?;
+        let target_len = read_vint(&mut reader)? as usize;
+        if target_len > data.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Redirection target exceeds record"));
+        }
+        let mut target_bytes = vec![0u8; target_len];
+        reader.read_exact(&mut target_bytes)?;
+        let target = String::from_utf8(target_bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Redirection {
+            kind,
+            target,
+            is_directory: flags & DIRECTORY_FLAG != 0,
+        })
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        write_vint(&mut data, self.kind.raw())?;
+        write_vint(&mut data, if self.is_directory { DIRECTORY_FLAG } else { 0 })?;
+        write_vint(&mut data, self.target.len() as u64)?;
+        data.extend_from_slice(self.target.as_bytes());
+        Ok(data)
+    }
+}
@@ -0,0 +1,68 @@
+// src/structures/service_header.rs
+
+use crate::structures::block::{Block, BaseBlock};
+use crate::structures::file_header::FileHeader;
+use std::io::{self, Read, Write};
+
+/// The well-known services RAR stores in service headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceKind {
+    /// `CMT`: the archive comment.
+    Comment,
+    /// `QO`: the quick open index of the archive's headers.
+    QuickOpen,
+    /// `ACL`: an NTFS access control list for the preceding file.
+    Acl,
+    /// `STM`: an NTFS alternate data stream of the preceding file.
+    Stream,
+    /// `RR`: the recovery record.
+    Recovery,
+    Other(String),
+}
+
+/// A service header (type 3). It has the layout of a file header; the file
+/// name holds the service name and the data area holds the service data.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ServiceHeader {
+    pub file_header: FileHeader,
+}
+
+impl ServiceHeader {
+    /// Returns the service name, such as `CMT` or `QO`.
+    pub fn name(&self) -> &str {
+        &self.file_header.file_name
+    }
+
+    pub fn kind(&self) -> ServiceKind {
+        match self.name() {
+            "CMT" => ServiceKind::Comment,
+            "QO" => ServiceKind::QuickOpen,
+            "ACL" => ServiceKind::Acl,
+            "STM" => ServiceKind::Stream,
+            "RR" => ServiceKind::Recovery,
+            other => ServiceKind::Other(other.to_string()),
+        }
+    }
+}
+
+impl Block for ServiceHeader {
+    fn get_base(&self) -> &BaseBlock {
+        &self.file_header.base
+    }
+
+    fn get_mut_base(&mut self) -> &mut BaseBlock {
+        &mut self.file_header.base
+    }
+
+    fn header_type(&self) -> u64 {
+        3
+    }
+
+    fn encode_data(&self, writer: &mut dyn Write) -> io::Result<()> {
+        self.file_header.encode_data(writer)
+    }
+
+    fn decode_data(&mut self, reader: &mut dyn Read) -> io::Result<()> {
+        self.file_header.decode_data(reader)
+    }
+}
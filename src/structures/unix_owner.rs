@@ -0,0 +1,77 @@
+// src/structures/unix_owner.rs
+
+use crate::decoder::read_vint;
+use crate::encoder::write_vint;
+use std::io::{self, Cursor, Read};
+
+const USER_NAME_FLAG: u64 = 0x0001;
+const GROUP_NAME_FLAG: u64 = 0x0002;
+const USER_ID_FLAG: u64 = 0x0004;
+const GROUP_ID_FLAG: u64 = 0x0008;
+
+/// The Unix owner extra record (type 0x06).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnixOwner {
+    pub user_name: Option<String>,
+    pub group_name: Option<String>,
+    pub user_id: Option<u64>,
+    pub group_id: Option<u64>,
+}
+
+impl UnixOwner {
+    pub fn decode(data: &[u8]) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        let flags = read_vint(&mut reader)?;
+        let mut owner = UnixOwner::default();
+        if flags & USER_NAME_FLAG != 0 {
+            owner.user_name = Some(read_name(&mut reader, data.len())?);
+        }
+        if flags & GROUP_NAME_FLAG != 0 {
+            owner.group_name = Some(read_name(&mut reader, data.len())?);
+        }
+        if flags & USER_ID_FLAG != 0 {
+            owner.user_id = Some(read_vint(&mut reader)?);
+        }
+        if flags & GROUP_ID_FLAG != 0 {
+            owner.group_id = Some(read_vint(&mut reader)?);
+        }
+        Ok(owner)
+    }
+
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let mut flags = 0;
+        if self.user_name.is_some() {
+            flags |= USER_NAME_FLAG;
+        }
+        if self.group_name.is_some() {
+            flags |= GROUP_NAME_FLAG;
+        }
+        if self.user_id.is_some() {
+            flags |= USER_ID_FLAG;
+        }
+        if self.group_id.is_some() {
+            flags |= GROUP_ID_FLAG;
+        }
+
+        let mut data = Vec::new();
+        write_vint(&mut data, flags)?;
+        for name in [&self.user_name, &self.group_name].into_iter().flatten() {
+            write_vint(&mut data, name.len() as u64)?;
+            data.extend_from_slice(name.as_bytes());
+        }
+        for id in [self.user_id, self.group_id].into_iter().flatten() {
+            write_vint(&mut data, id)?;
+        }
+        Ok(data)
+    }
+}
+
+fn read_name(reader: &mut dyn Read, record_len: usize) -> io::Result<String> {
+    let name_len = read_vint(reader)? as usize;
+    if name_len > record_len {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Owner name exceeds record"));
+    }
+    let mut name_bytes = vec![0u8; name_len];
+    reader.read_exact(&mut name_bytes)?;
+    String::from_utf8(name_bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,135 @@
+// src/volume.rs
+
+//! Locating the volumes of multi-volume archives.
+
+use log::debug;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Opens the volumes of a multi-volume archive for `Archive::from_volumes`.
+pub trait VolumeSource {
+    type Reader: Read;
+
+    /// Opens volume `index`, counting from 0 for the first volume.
+    fn open_volume(&mut self, index: u64) -> io::Result<Self::Reader>;
+}
+
+/// Volumes stored as files named after the first one, either
+/// `name.part1.rar`, `name.part2.rar`, ... or `name.rar`, `name.r00`, ...
+pub struct FileVolumes {
+    first: PathBuf,
+}
+
+impl FileVolumes {
+    pub fn new<P: Into<PathBuf>>(first: P) -> Self {
+        FileVolumes { first: first.into() }
+    }
+
+    /// Returns the path of volume `index`.
+    pub fn volume_path(&self, index: u64) -> io::Result<PathBuf> {
+        if index == 0 {
+            return Ok(self.first.clone());
+        }
+        let file_name = self
+            .first
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| unknown_naming(&self.first))?;
+        let name = part_volume_name(file_name, index)
+            .or_else(|| old_style_volume_name(file_name, index))
+            .ok_or_else(|| unknown_naming(&self.first))?;
+        Ok(self.first.with_file_name(name))
+    }
+}
+
+impl VolumeSource for FileVolumes {
+    type Reader = BufReader<File>;
+
+    fn open_volume(&mut self, index: u64) -> io::Result<Self::Reader> {
+        let path = self.volume_path(index)?;
+        debug!("[FileVolumes::open_volume] Opening volume {} at {:?}", index, path);
+        match File::open(&path) {
+            Ok(file) => Ok(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Missing volume '{}'", path.display()),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Numbers `name.partN.rar` volumes on from the first one, keeping the width
+/// of its number.
+fn part_volume_name(file_name: &str, index: u64) -> Option<String> {
+    let stem = strip_suffix_ignore_case(file_name, ".rar")?;
+    let digits_start = stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (prefix, digits) = stem.split_at(digits_start);
+    if digits.is_empty() || !prefix.to_ascii_lowercase().ends_with(".part") {
+        return None;
+    }
+    let number = digits.parse::<u64>().ok()? + index;
+    let extension = &file_name[stem.len()..];
+    Some(format!("{}{:0width$}{}", prefix, number, extension, width = digits.len()))
+}
+
+/// Names old-style volumes: `name.rar` is followed by `name.r00` to
+/// `name.r99`, then `name.s00` and so on.
+fn old_style_volume_name(file_name: &str, index: u64) -> Option<String> {
+    let stem = strip_suffix_ignore_case(file_name, ".rar")?;
+    let number = index - 1;
+    let first_letter = file_name[stem.len() + 1..].chars().next()?;
+    let letter = char::from_u32(first_letter as u32 + (number / 100) as u32).filter(char::is_ascii_alphabetic)?;
+    Some(format!("{}.{}{:02}", stem, letter, number % 100))
+}
+
+fn strip_suffix_ignore_case<'a>(file_name: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = file_name.len().checked_sub(suffix.len())?;
+    file_name
+        .get(split..)
+        .filter(|tail| tail.eq_ignore_ascii_case(suffix))
+        .map(|_| &file_name[..split])
+}
+
+fn unknown_naming(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Cannot tell how the volumes after '{}' are named", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(first: &str, count: u64) -> Vec<String> {
+        let volumes = FileVolumes::new(first);
+        (0..count)
+            .map(|index| volumes.volume_path(index).unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn part_names_keep_the_number_width() {
+        assert_eq!(names("data.part1.rar", 3), ["data.part1.rar", "data.part2.rar", "data.part3.rar"]);
+        assert_eq!(names("dir/data.part09.rar", 3)[1..], ["dir/data.part10.rar", "dir/data.part11.rar"]);
+        assert_eq!(names("DATA.PART001.RAR", 2)[1], "DATA.PART002.RAR");
+    }
+
+    #[test]
+    fn old_style_names_count_through_the_letters() {
+        let volumes = FileVolumes::new("data.rar");
+        let name = |index| volumes.volume_path(index).unwrap().to_string_lossy().into_owned();
+        assert_eq!(name(1), "data.r00");
+        assert_eq!(name(100), "data.r99");
+        assert_eq!(name(101), "data.s00");
+        assert_eq!(FileVolumes::new("DATA.RAR").volume_path(2).unwrap(), PathBuf::from("DATA.R01"));
+    }
+
+    #[test]
+    fn other_names_are_rejected() {
+        let error = FileVolumes::new("data.zip").volume_path(1).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
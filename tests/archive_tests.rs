@@ -1,15 +1,17 @@
 // tests/archive_tests.rs
 
-use app::archive::Archive;
+use app::archive::{Archive, CrcMode};
+use app::encoder::encode_block;
 use app::structures::{
     block::{Block, BaseBlock},
     base::BlockHeader,
+    extra_area::ExtraAreaRecord,
     end_of_archive_header::EndOfArchiveHeader,
     file_header::FileHeader,
-    general_block_header::GeneralBlockHeader,
+    general_block_header::{GeneralBlockHeader, HeaderFlags},
     main_archive_header::MainArchiveHeader,
 };
-use std::io::Cursor;
+use std::io::{Cursor, ErrorKind};
 
 fn build_block(block: &mut dyn Block) -> Vec<u8> {
     let mut buffer = Vec::new();
@@ -85,10 +87,10 @@ fn test_read_file_data() {
     let mut file_header = FileHeader {
         base: BaseBlock {
             general_header: GeneralBlockHeader {
-                header_flags: 0x0001,
                 data_size: Some(compressed_data.len() as u64),
                 ..Default::default()
             },
+            ..Default::default()
         },
         file_name: "test_file.txt".to_string(),
         ..Default::default()
@@ -118,3 +120,203 @@ fn test_read_file_data() {
         }
     }
 }
+
+#[test]
+fn test_extra_area_round_trips_alongside_data() {
+    let extra_area = vec![ExtraAreaRecord { kind: 7, data: vec![1, 2, 3] }];
+    let mut file_header = FileHeader {
+        base: BaseBlock {
+            general_header: GeneralBlockHeader {
+                data_size: Some(2),
+                ..Default::default()
+            },
+            extra_area: extra_area.clone(),
+        },
+        file_name: "extra.txt".to_string(),
+        ..Default::default()
+    };
+
+    let mut mock_data: Vec<u8> = vec![
+        0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00, // Signature
+    ];
+    mock_data.extend_from_slice(&build_block(&mut file_header));
+    mock_data.extend_from_slice(&[9, 9]);
+
+    let mut archive = Archive::new(Cursor::new(mock_data)).unwrap();
+    archive.set_crc_mode(CrcMode::Strict);
+    match archive.next() {
+        Some(Ok(BlockHeader::File(header))) => {
+            let general_header = &header.base.general_header;
+            assert!(general_header.header_flags.has_extra_area());
+            assert_eq!(general_header.extra_area_size, Some(5));
+            assert_eq!(header.base.extra_area, extra_area);
+            assert_eq!(header.file_name, "extra.txt");
+            assert_eq!(archive.read_file_data(&header).unwrap(), vec![9, 9]);
+        }
+        other => panic!("Expected a FileHeader, but got {:?}", other),
+    }
+}
+
+fn archive_with_end_header(end_header_bytes: &[u8]) -> Archive<Cursor<Vec<u8>>> {
+    let mut mock_data: Vec<u8> = vec![
+        0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00, // Signature
+    ];
+    mock_data.extend_from_slice(end_header_bytes);
+    Archive::new(Cursor::new(mock_data)).unwrap()
+}
+
+/// The end of archive header written by WinRAR, with a valid CRC32.
+const END_HEADER: [u8; 8] = [0x1D, 0x77, 0x56, 0x51, 0x03, 0x05, 0x04, 0x00];
+
+#[test]
+fn test_strict_crc_mode_accepts_valid_header() {
+    let mut archive = archive_with_end_header(&END_HEADER);
+    archive.set_crc_mode(CrcMode::Strict);
+
+    let block = archive.next().unwrap().unwrap();
+    assert!(matches!(block, BlockHeader::End(_)));
+}
+
+#[test]
+fn test_strict_crc_mode_rejects_corrupted_header() {
+    let mut corrupted = END_HEADER;
+    corrupted[0] ^= 0xFF;
+    let mut archive = archive_with_end_header(&corrupted);
+    archive.set_crc_mode(CrcMode::Strict);
+
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_lenient_crc_mode_reports_mismatch_on_block() {
+    let mut corrupted = END_HEADER;
+    corrupted[0] ^= 0xFF;
+    let mut archive = archive_with_end_header(&corrupted);
+
+    match archive.next().unwrap().unwrap() {
+        BlockHeader::End(header) => assert!(header.base.general_header.has_crc_mismatch()),
+        other => panic!("Expected an EndOfArchiveHeader, but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encoded_blocks_pass_strict_crc_check() {
+    let mut mock_data: Vec<u8> = vec![
+        0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00, // Signature
+    ];
+    let mut main_header = MainArchiveHeader::default();
+    mock_data.extend_from_slice(&build_block(&mut main_header));
+
+    let mut file_header = FileHeader {
+        file_name: "test".to_string(),
+        ..Default::default()
+    };
+    mock_data.extend_from_slice(&build_block(&mut file_header));
+
+    let mut end_header = EndOfArchiveHeader::default();
+    mock_data.extend_from_slice(&build_block(&mut end_header));
+
+    let mut archive = Archive::new(Cursor::new(mock_data)).unwrap();
+    archive.set_crc_mode(CrcMode::Strict);
+    let blocks: Vec<_> = archive.map(Result::unwrap).collect();
+
+    assert_eq!(blocks.len(), 3);
+}
+
+/// A block of unknown type 0x42 with a 3-byte data area.
+fn unknown_block(header_flags: u64) -> BlockHeader {
+    BlockHeader::Unknown {
+        general_header: GeneralBlockHeader {
+            header_type: 0x42,
+            header_flags: HeaderFlags(header_flags),
+            data_size: Some(3),
+            ..Default::default()
+        },
+        raw: vec![0xAA, 0xBB],
+    }
+}
+
+fn archive_with_unknown_block(header_flags: u64) -> Archive<Cursor<Vec<u8>>> {
+    let mut mock_data: Vec<u8> = vec![
+        0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00, // Signature
+    ];
+    encode_block(&unknown_block(header_flags), &mut mock_data).unwrap();
+    mock_data.extend_from_slice(&[1, 2, 3]);
+    mock_data.extend_from_slice(&build_block(&mut EndOfArchiveHeader::default()));
+    Archive::new(Cursor::new(mock_data)).unwrap()
+}
+
+#[test]
+fn test_skippable_unknown_block_is_returned_and_skipped() {
+    let mut archive = archive_with_unknown_block(HeaderFlags::SKIP_IF_UNKNOWN);
+    archive.set_crc_mode(CrcMode::Strict);
+
+    match archive.next() {
+        Some(Ok(BlockHeader::Unknown { general_header, raw })) => {
+            assert_eq!(general_header.header_type, 0x42);
+            assert_eq!(general_header.data_size, Some(3));
+            assert_eq!(raw, vec![0xAA, 0xBB]);
+        }
+        other => panic!("Expected an unknown block, but got {:?}", other),
+    }
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::End(_)))));
+    assert!(archive.next().is_none());
+}
+
+#[test]
+fn test_unknown_block_without_skip_flag_is_an_error() {
+    let mut archive = archive_with_unknown_block(0);
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_unknown_block_round_trips_through_encoder() {
+    let mut archive = archive_with_unknown_block(HeaderFlags::SKIP_IF_UNKNOWN);
+    let block = archive.next().unwrap().unwrap();
+
+    let mut original = Vec::new();
+    encode_block(&unknown_block(HeaderFlags::SKIP_IF_UNKNOWN), &mut original).unwrap();
+    let mut encoded = Vec::new();
+    encode_block(&block, &mut encoded).unwrap();
+    assert_eq!(encoded, original);
+}
+
+#[test]
+fn test_end_of_archive_flags_round_trip() {
+    let mut end_header = EndOfArchiveHeader {
+        end_flags: 0x0001,
+        ..Default::default()
+    };
+    assert!(!end_header.is_last_volume());
+    let mut reader = Cursor::new(build_block(&mut end_header));
+    let general_header = app::decoder::read_general_block_header(&mut reader).unwrap();
+    let decoded = EndOfArchiveHeader::decode(general_header, &mut reader).unwrap();
+    assert_eq!(decoded.end_flags, 0x0001);
+    assert!(!decoded.is_last_volume());
+    assert!(EndOfArchiveHeader::default().is_last_volume());
+}
+
+#[test]
+fn test_volume_end_is_reported_separately() {
+    let mut data = vec![0x52, 0x61, 0x72, 0x21, 0x1A, 0x07, 0x01, 0x00];
+    let mut main_header = MainArchiveHeader {
+        archive_flags: 0x0001,
+        ..Default::default()
+    };
+    data.extend_from_slice(&build_block(&mut main_header));
+    let mut end_header = EndOfArchiveHeader {
+        end_flags: 0x0001,
+        ..Default::default()
+    };
+    data.extend_from_slice(&build_block(&mut end_header));
+
+    let mut archive = Archive::new(Cursor::new(data)).unwrap();
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::Main(header))) if header.is_volume()));
+    match archive.next() {
+        Some(Ok(BlockHeader::VolumeEnd(header))) => assert!(!header.is_last_volume()),
+        other => panic!("Expected a VolumeEnd, but got {:?}", other),
+    }
+    assert!(archive.next().is_none());
+}
@@ -0,0 +1,45 @@
+# tests/assets/scripts/encrypted_files.py
+#
+# Writes tests/assets/encrypted_files.rar. Run from anywhere:
+#     python3 tests/assets/scripts/encrypted_files.py
+
+import hashlib
+import hmac
+import struct
+import zlib
+
+from rar5_writer import SIGNATURE, block, encrypt, kdf, rar5_packed_entry, vint, write
+
+password = b'password'
+salt = bytes(range(0x20, 0x30))
+count = 15
+key, hash_key, check_value = kdf(password, salt, count)
+
+
+def crc_mac(crc):
+    """The CRC32 of an encrypted entry, stored as a MAC keyed by the hash key."""
+    digest = hmac.new(hash_key, struct.pack('<I', crc), hashlib.sha256).digest()
+    mac = 0
+    for i, b in enumerate(digest):
+        mac ^= b << ((i & 3) * 8)
+    return mac
+
+
+def entry(name, size, crc, packed, comp_info, iv):
+    # File encryption record: version 0, flags 3 (password check, checksums as MACs).
+    record = vint(0) + vint(0x3) + bytes([count]) + salt + iv + check_value
+    extra = vint(len(vint(1) + record)) + vint(1) + record
+    data = encrypt(key, iv, packed)[16:]
+    body = vint(2) + vint(0x3) + vint(len(extra)) + vint(len(data)) + vint(4) + vint(size) \
+        + vint(0x20) + struct.pack('<I', crc_mac(crc)) + vint(comp_info) \
+        + vint(0) + vint(len(name)) + name + extra
+    return block(body) + data
+
+
+stored = b'Only the right password reveals this.\n'
+out = SIGNATURE
+out += block(vint(1) + vint(0) + vint(0))
+out += entry(b'stored.txt', len(stored), zlib.crc32(stored), stored, 0, bytes(0x40 + i for i in range(16)))
+out += entry(b'compressed.txt', 15498, 0x9bd160fa, rar5_packed_entry(), 0x180, bytes(0x60 + i for i in range(16)))
+out += block(vint(5) + vint(0) + vint(0))
+write('encrypted_files.rar', out)
@@ -0,0 +1,29 @@
+# tests/assets/scripts/encrypted_headers.py
+#
+# Writes tests/assets/encrypted_headers.rar. Run from anywhere:
+#     python3 tests/assets/scripts/encrypted_headers.py
+
+import struct
+import zlib
+
+from rar5_writer import SIGNATURE, block, encrypt, kdf, vint, write
+
+password = b'password'
+salt = bytes(range(16))
+count = 15
+key, _, check_value = kdf(password, salt, count)
+
+content = b'Encrypted headers hide this name.\n'
+name = b'secret.txt'
+ivs = [bytes([0x10 + i] * 16) for i in range(3)]
+
+out = SIGNATURE
+# Archive encryption header: version 0, flags 1 (password check present).
+out += block(vint(4) + vint(0) + vint(0) + vint(1) + bytes([count]) + salt + check_value)
+out += encrypt(key, ivs[0], block(vint(1) + vint(0) + vint(0)))
+file_header = vint(2) + vint(2) + vint(len(content)) + vint(4) + vint(len(content)) + vint(0x20) \
+    + struct.pack('<I', zlib.crc32(content)) + vint(0) + vint(0) + vint(len(name)) + name
+out += encrypt(key, ivs[1], block(file_header))
+out += content
+out += encrypt(key, ivs[2], block(vint(5) + vint(0) + vint(0)))
+write('encrypted_headers.rar', out)
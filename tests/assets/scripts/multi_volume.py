@@ -0,0 +1,48 @@
+# tests/assets/scripts/multi_volume.py
+#
+# Writes tests/assets/multi.part1.rar to multi.part3.rar. Run from anywhere:
+#     python3 tests/assets/scripts/multi_volume.py
+
+import struct
+import zlib
+
+from rar5_writer import SIGNATURE, block, rar5_packed_entry, vint, write
+
+
+def main_header(number):
+    # Archive flags: 1 volume, 2 volume number present (not in the first volume).
+    flags = 0x1 | (0x2 if number else 0)
+    return block(vint(1) + vint(0) + vint(flags) + (vint(number) if number else b''))
+
+
+def end_header(last):
+    return block(vint(5) + vint(0) + vint(0 if last else 1))
+
+
+def file_header(name, size, crc, data, comp_info, split_flags=0):
+    # Header flags: 0x08 split before, 0x10 split after.
+    body = vint(2) + vint(0x2 | split_flags) + vint(len(data)) + vint(4) + vint(size) + vint(0x20) \
+        + struct.pack('<I', crc) + vint(comp_info) + vint(0) + vint(len(name)) + name
+    return block(body) + data
+
+
+packed = rar5_packed_entry()
+parts = [packed[:1000], packed[1000:2000], packed[2000:]]
+first = b'The first entry fits in the first volume.\n'
+last = b'The last entry follows the split one.\n'
+
+volumes = [
+    SIGNATURE + main_header(0)
+    + file_header(b'first.txt', len(first), zlib.crc32(first), first, 0)
+    + file_header(b'split.bin', 15498, zlib.crc32(parts[0]), parts[0], 0x180, 0x10)
+    + end_header(False),
+    SIGNATURE + main_header(1)
+    + file_header(b'split.bin', 15498, zlib.crc32(parts[1]), parts[1], 0x180, 0x18)
+    + end_header(False),
+    SIGNATURE + main_header(2)
+    + file_header(b'split.bin', 15498, 0x9bd160fa, parts[2], 0x180, 0x08)
+    + file_header(b'last.txt', len(last), zlib.crc32(last), last, 0)
+    + end_header(True),
+]
+for number, volume in enumerate(volumes, 1):
+    write('multi.part%d.rar' % number, volume)
@@ -0,0 +1,71 @@
+# tests/assets/scripts/rar5_writer.py
+#
+# Minimal RAR5 block writer used to build the test fixtures in tests/assets.
+# It follows the RAR 5.0 technote directly and shares no code with the crate.
+
+import hashlib
+import hmac
+import os
+import struct
+import zlib
+
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+
+SIGNATURE = b'Rar!\x1a\x07\x01\x00'
+ROOT = os.path.abspath(os.path.join(os.path.dirname(__file__), '..', '..', '..'))
+
+
+def asset(name):
+    return os.path.join(ROOT, 'tests', 'assets', name)
+
+
+def rar5_packed_entry():
+    """The packed data of a compressed entry of Rar5.rar, an archive made by rar."""
+    data = open(os.path.join(ROOT, 'Rar5.rar'), 'rb').read()
+    return data[57093:57093 + 2728]
+
+
+def vint(n):
+    out = bytearray()
+    while True:
+        b = n & 0x7f
+        n >>= 7
+        if n:
+            out.append(b | 0x80)
+        else:
+            out.append(b)
+            return bytes(out)
+
+
+def block(body):
+    header = vint(len(body)) + body
+    return struct.pack('<I', zlib.crc32(header)) + header
+
+
+def kdf(password, salt, count):
+    """PBKDF2-HMAC-SHA256 as RAR5 uses it: the key, the hash key and the check value."""
+    u = hmac.new(password, salt + b'\0\0\0\1', hashlib.sha256).digest()
+    fn = bytearray(u)
+    outs = []
+    for n in [(1 << count) - 1, 16, 16]:
+        for _ in range(n):
+            u = hmac.new(password, u, hashlib.sha256).digest()
+            fn = bytearray(a ^ b for a, b in zip(fn, u))
+        outs.append(bytes(fn))
+    check = bytearray(8)
+    for i, b in enumerate(outs[2]):
+        check[i % 8] ^= b
+    check_value = bytes(check) + hashlib.sha256(check).digest()[:4]
+    return outs[0], outs[1], check_value
+
+
+def encrypt(key, iv, data):
+    """AES-256-CBC over `data` padded with zeros, prefixed with the IV."""
+    data = data + b'\0' * (-len(data) % 16)
+    encryptor = Cipher(algorithms.AES(key), modes.CBC(iv)).encryptor()
+    return iv + encryptor.update(data) + encryptor.finalize()
+
+
+def write(name, data):
+    open(asset(name), 'wb').write(data)
+    print(name, len(data))
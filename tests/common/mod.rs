@@ -0,0 +1,55 @@
+// tests/common/mod.rs
+
+//! Helpers shared by the integration tests. Each test crate uses a subset.
+#![allow(dead_code)]
+
+/// Writes MSB-first bit streams, mirroring the decoder's bit reader.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_count: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        for shift in (0..bits).rev() {
+            if self.bit_count.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> shift) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.bit_count % 8);
+            self.bit_count += 1;
+        }
+    }
+}
+
+/// Builds a single-block LZ stream with flat Huffman tables: every main symbol
+/// is 9 bits, distances 6 bits, low distances 4 bits and repeat lengths 6 bits.
+/// Each symbol is a `(code, bit_count)` pair written as-is.
+pub fn lz_stream(symbols: &[(u32, u32)]) -> Vec<u8> {
+    let mut body = BitWriter::default();
+    for _ in 0..20 {
+        body.write(5, 4);
+    }
+    let table_lengths = [(306, 9), (64, 6), (16, 4), (44, 6)];
+    for (count, length) in table_lengths {
+        for _ in 0..count {
+            body.write(length, 5);
+        }
+    }
+    for &(value, bits) in symbols {
+        body.write(value, bits);
+    }
+
+    let block_size = body.bytes.len();
+    let bit_size = body.bit_count - (block_size - 1) * 8;
+    let flags = 0x80 | 0x40 | 0x08 | (bit_size as u8 - 1);
+    let checksum = 0x5A ^ flags ^ (block_size as u8) ^ ((block_size >> 8) as u8);
+    let mut stream = vec![flags, checksum, block_size as u8, (block_size >> 8) as u8];
+    stream.extend_from_slice(&body.bytes);
+    stream
+}
+
+pub fn literal(byte: u8) -> (u32, u32) {
+    (byte as u32, 9)
+}
@@ -0,0 +1,48 @@
+// tests/compression_info_tests.rs
+
+use app::structures::compression_info::CompressionInfo;
+
+#[test]
+fn test_rar5_compression_info_fields() {
+    // Version 0, solid, method 3, dictionary exponent 5 (4 MiB).
+    let info = CompressionInfo::new((5 << 10) | (3 << 7) | 0x40);
+
+    assert_eq!(info.version(), 0);
+    assert!(info.is_solid());
+    assert_eq!(info.method(), 3);
+    assert!(!info.is_stored());
+    assert_eq!(info.dictionary_size(), 4 * 1024 * 1024);
+    assert!(!info.uses_extended_distances());
+}
+
+#[test]
+fn test_stored_compression_info() {
+    let info = CompressionInfo::default();
+
+    assert!(info.is_stored());
+    assert!(!info.is_solid());
+    assert_eq!(info.dictionary_size(), 128 * 1024);
+}
+
+#[test]
+fn test_rar5_ignores_fifth_exponent_bit() {
+    let info = CompressionInfo::new((0x1F << 10) | (1 << 7));
+    assert_eq!(info.dictionary_size(), (128 * 1024) << 15);
+}
+
+#[test]
+fn test_rar7_dictionary_size_with_fraction() {
+    // Version 1, exponent 17 (16 GiB), fraction 8/32.
+    let info = CompressionInfo::new((8 << 15) | (17 << 10) | (5 << 7) | 1);
+
+    assert_eq!(info.version(), 1);
+    assert_eq!(info.method(), 5);
+    assert_eq!(info.dictionary_size(), (16u64 << 30) + (16u64 << 30) / 32 * 8);
+    assert!(info.uses_extended_distances());
+}
+
+#[test]
+fn test_rar7_header_with_rar5_compatible_data() {
+    let info = CompressionInfo::new(0x0010_0000 | (3 << 7) | 1);
+    assert!(!info.uses_extended_distances());
+}
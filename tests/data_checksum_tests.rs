@@ -0,0 +1,56 @@
+// tests/data_checksum_tests.rs
+
+use app::archive::Archive;
+use app::integrity::IntegrityError;
+use app::structures::base::BlockHeader;
+use std::io::{Cursor, ErrorKind};
+
+/// Offset of the stored data in tests/assets/uncompressed.rar.
+const DATA_OFFSET: usize = 0x4F;
+
+fn read_entry(archive_bytes: Vec<u8>, verify_data: bool) -> std::io::Result<Vec<u8>> {
+    let mut archive = Archive::new(Cursor::new(archive_bytes)).unwrap();
+    archive.set_verify_data(verify_data);
+    loop {
+        match archive.next() {
+            Some(Ok(BlockHeader::File(header))) => return archive.read_file_data(&header),
+            Some(Ok(_)) => continue,
+            other => panic!("Expected a FileHeader, but got {:?}", other),
+        }
+    }
+}
+
+fn corrupted_archive() -> Vec<u8> {
+    let mut archive_bytes = std::fs::read("tests/assets/uncompressed.rar").unwrap();
+    archive_bytes[DATA_OFFSET] ^= 0x20;
+    archive_bytes
+}
+
+#[test]
+fn test_matching_crc32_is_accepted() {
+    let archive_bytes = std::fs::read("tests/assets/uncompressed.rar").unwrap();
+    let data = read_entry(archive_bytes, true).unwrap();
+    assert_eq!(data, b"This is an uncompressed test file.\n");
+}
+
+#[test]
+fn test_crc32_mismatch_names_the_entry() {
+    let error = read_entry(corrupted_archive(), true).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let integrity_error = error.get_ref().unwrap().downcast_ref::<IntegrityError>().unwrap();
+    match integrity_error {
+        IntegrityError::ChecksumMismatch { expected, actual, name } => {
+            assert_eq!(name, "uncompressed_test.txt");
+            assert_eq!(*expected, 0x73A58602);
+            assert_ne!(actual, expected);
+        }
+        other => panic!("Expected a ChecksumMismatch, but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_verification_can_be_turned_off() {
+    let data = read_entry(corrupted_archive(), false).unwrap();
+    assert_eq!(data, b"this is an uncompressed test file.\n");
+}
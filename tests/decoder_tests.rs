@@ -4,7 +4,7 @@
 // We also need to import the functions and structs from the `app` crate.
 
 use app::decoder::*;
-use app::structures::general_block_header::GeneralBlockHeader;
+use app::structures::general_block_header::{GeneralBlockHeader, HeaderFlags};
 use std::io::Cursor;
 
 #[test]
@@ -62,8 +62,10 @@ fn test_general_block_header_basic() {
             crc32: 0x11223344,
             header_size: 10,
             header_type: 1,
-            header_flags: 0,
+            header_flags: HeaderFlags(0),
+            extra_area_size: None,
             data_size: None,
+            computed_crc32: None,
         }
     );
 }
@@ -74,13 +76,13 @@ fn test_read_general_block_header_with_data_size() {
     // CRC32: 0xAABBCCDD (little-endian)
     // Header Size: 15 (vint 0x0F)
     // Header Type: 2 (vint 0x02)
-    // Header Flags: 0x0001 (vint 0x01) - indicates presence of Data Size
+    // Header Flags: 0x0002 (vint 0x02) - indicates presence of Data Size
     // Data Size: 1024 (vint 0x80, 0x08)
     let data: Vec<u8> = vec![
         0xDD, 0xCC, 0xBB, 0xAA, // CRC32
         0x0F,   // Header Size (15)
         0x02,   // Header Type (2)
-        0x01,   // Header Flags (1)
+        0x02,   // Header Flags (2)
         0x80, 0x08, // Data Size (1024)
     ];
     let mut cursor = Cursor::new(data);
@@ -92,8 +94,64 @@ fn test_read_general_block_header_with_data_size() {
             crc32: 0xAABBCCDD,
             header_size: 15,
             header_type: 2,
-            header_flags: 1,
+            header_flags: HeaderFlags(HeaderFlags::DATA_AREA),
+            extra_area_size: None,
             data_size: Some(1024),
+            computed_crc32: None,
         }
     );
 }
+
+#[test]
+fn test_read_general_block_header_with_extra_area_and_data_size() {
+    // The extra area size comes before the data size.
+    let data: Vec<u8> = vec![
+        0xDD, 0xCC, 0xBB, 0xAA, // CRC32
+        0x0F,   // Header Size (15)
+        0x02,   // Header Type (2)
+        0x03,   // Header Flags (3)
+        0x0B,   // Extra Area Size (11)
+        0x80, 0x08, // Data Size (1024)
+    ];
+    let mut cursor = Cursor::new(data);
+    let header = read_general_block_header(&mut cursor).unwrap();
+
+    assert!(header.header_flags.has_extra_area());
+    assert!(header.header_flags.has_data_area());
+    assert_eq!(header.extra_area_size, Some(11));
+    assert_eq!(header.data_size, Some(1024));
+}
+
+#[test]
+fn test_read_checked_block_header_computes_crc32() {
+    // The main archive header of tests/assets/uncompressed.rar.
+    let data: Vec<u8> = vec![
+        0x33, 0x92, 0xB5, 0xE5, // CRC32
+        0x0A, // Header Size (10)
+        0x01, 0x05, 0x06, 0x00, 0x05, 0x01, 0x01, 0x80, 0x80, 0x00,
+        0xAA, // First byte of the next block
+    ];
+    let mut cursor = Cursor::new(data);
+    let (header, header_reader) = read_checked_block_header(&mut cursor).unwrap();
+
+    assert_eq!(header.computed_crc32, Some(0xE5B59233));
+    assert!(!header.has_crc_mismatch());
+    assert_eq!(header_reader.get_ref().len(), 15);
+    assert_eq!(cursor.position(), 15);
+}
+
+#[test]
+fn test_read_checked_block_header_reports_mismatch() {
+    let data: Vec<u8> = vec![0x00, 0x00, 0x00, 0x00, 0x03, 0x05, 0x04, 0x00];
+    let mut cursor = Cursor::new(data);
+    let (header, _) = read_checked_block_header(&mut cursor).unwrap();
+
+    assert!(header.has_crc_mismatch());
+}
+
+#[test]
+fn test_read_checked_block_header_rejects_truncated_header() {
+    let data: Vec<u8> = vec![0x00, 0x00, 0x00, 0x00, 0x0A, 0x05];
+    let mut cursor = Cursor::new(data);
+    assert!(read_checked_block_header(&mut cursor).is_err());
+}
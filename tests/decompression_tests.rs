@@ -1,7 +1,12 @@
 // tests/decompression_tests.rs
 
-use app::archive::Archive;
+mod common;
+
+use app::archive::{Archive, CrcMode};
 use app::structures::base::BlockHeader;
+use app::decompression::get_decompressor;
+use app::structures::compression_info::CompressionInfo;
+use common::{literal, lz_stream};
 use std::fs::File;
 
 #[test]
@@ -11,18 +16,131 @@ fn test_uncompressed_rar_archive() {
 
     let mut found_file = false;
     while let Some(block) = archive.next() {
-        match block.unwrap() {
-            BlockHeader::File(file_header) => {
-                if file_header.file_name == "uncompressed_test.txt" {
-                    found_file = true;
-                    let decompressed_data = archive.read_file_data(&file_header).unwrap();
-                    let original_content = "This is an uncompressed test file.\n";
-                    assert_eq!(decompressed_data, original_content.as_bytes());
-                }
+        if let BlockHeader::File(file_header) = block.unwrap() {
+            if file_header.file_name == "uncompressed_test.txt" {
+                found_file = true;
+                let decompressed_data = archive.read_file_data(&file_header).unwrap();
+                let original_content = "This is an uncompressed test file.\n";
+                assert_eq!(decompressed_data, original_content.as_bytes());
             }
-            _ => {}
         }
     }
 
     assert!(found_file, "The test file was not found in the archive.");
 }
+
+const CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+#[test]
+fn test_rar5_sample_extracts_every_entry() {
+    let mut archive = Archive::new(File::open("Rar5.rar").unwrap()).unwrap();
+    archive.set_crc_mode(CrcMode::Strict);
+
+    let mut entries = Vec::new();
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            // read_file_data checks the data against the stored CRC32.
+            let data = archive.read_file_data(&header).unwrap();
+            assert_eq!(Some(data.len() as u64), header.unpacked_size, "{}", header.file_name);
+            assert!(header.is_directory() || header.data_crc32.is_some(), "{}", header.file_name);
+            entries.push((header.file_name, data.len(), CRC32.checksum(&data)));
+        }
+    }
+
+    let expected = [
+        ("exe/test.exe", 45056, 0xcfb1_09c8),
+        ("jpg/test.jpg", 40372, 0x0888_14e3),
+        ("тест.txt", 15498, 0x9bd1_60fa),
+        ("Empty", 0, 0),
+        ("exe", 0, 0),
+        ("jpg", 0, 0),
+    ];
+    let expected: Vec<(String, usize, u32)> =
+        expected.iter().map(|&(name, len, crc)| (name.to_string(), len, crc)).collect();
+    assert_eq!(entries, expected);
+}
+
+/// Decompresses a data area of `Rar5.rar` located by its offset and length.
+/// A low-level check of the decoder alone; `test_rar5_sample_extracts_every_entry`
+/// covers the same entries through `Archive`.
+fn decompress_rar5_sample(offset: usize, length: usize) -> Vec<u8> {
+    let archive = std::fs::read("Rar5.rar").unwrap();
+    let compressed_data = &archive[offset..offset + length];
+    // Compression info 0x180: version 0, method 3 ("normal"), 128 KiB dictionary.
+    app::decompression::decompress_data(compressed_data, CompressionInfo::new(0x180)).unwrap()
+}
+
+#[test]
+fn test_lz_decompresses_jpg_entry() {
+    let data = decompress_rar5_sample(18062, 38984);
+    assert_eq!(data.len(), 40372);
+    assert_eq!(CRC32.checksum(&data), 0x0888_14e3);
+}
+
+#[test]
+fn test_lz_decompresses_text_entry() {
+    let data = decompress_rar5_sample(57093, 2728);
+    assert_eq!(data.len(), 15498);
+    assert_eq!(CRC32.checksum(&data), 0x9bd1_60fa);
+}
+
+#[test]
+fn test_lz_decompresses_exe_entry_with_filters() {
+    let data = decompress_rar5_sample(72, 17943);
+    assert_eq!(data.len(), 45056);
+    assert_eq!(CRC32.checksum(&data), 0xcfb1_09c8);
+}
+
+#[test]
+fn test_lz_streams_input_and_output_in_small_pieces() {
+    let archive = std::fs::read("Rar5.rar").unwrap();
+    for (offset, length) in [(72, 17943), (18062, 38984)] {
+        let compressed_data = &archive[offset..offset + length];
+        let expected = decompress_rar5_sample(offset, length);
+
+        let mut decompressor = get_decompressor(CompressionInfo::new(0x180)).unwrap();
+        decompressor.start_entry();
+        let mut pieces = compressed_data.chunks(997).peekable();
+        let mut output = Vec::new();
+        let mut buffer = [0u8; 613];
+        loop {
+            match decompressor.read(&mut buffer).unwrap() {
+                0 => match pieces.next() {
+                    Some(piece) => decompressor.feed(piece, pieces.peek().is_none()),
+                    None => break,
+                },
+                count => output.extend_from_slice(&buffer[..count]),
+            }
+        }
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn test_lz_filter_block_larger_than_the_dictionary() {
+    const FILTER_LENGTH: u32 = 200_000;
+    // Main symbol 256 starts a filter: a one-byte block start of 0, a
+    // three-byte block length, type 0 (DELTA) and one channel.
+    let mut symbols = vec![(256, 9), (0, 2), (0, 8), (2, 2)];
+    symbols.extend(FILTER_LENGTH.to_le_bytes()[..3].iter().map(|&byte| (byte as u32, 8)));
+    symbols.extend([(0, 3), (0, 5)]);
+    // A literal followed by matches of 4097 bytes at distance 1, made of
+    // length slot 43 with all 9 extra bits set and distance slot 0.
+    symbols.push(literal(1));
+    let mut written = 1;
+    while written + 4097 <= FILTER_LENGTH {
+        symbols.extend([(262 + 43, 9), (511, 9), (0, 6)]);
+        written += 4097;
+    }
+    symbols.extend((written..FILTER_LENGTH).map(|_| literal(1)));
+
+    // Method 3 with a 128 KiB dictionary, smaller than the filter block.
+    let compression_info = CompressionInfo::new(3 << 7);
+    assert_eq!(compression_info.dictionary_size(), 128 * 1024);
+    let data = app::decompression::decompress_data(&lz_stream(&symbols), compression_info).unwrap();
+
+    // Undoing the delta of a run of ones counts down from 0xFF.
+    let expected: Vec<u8> = (0..FILTER_LENGTH).map(|index| 0u8.wrapping_sub(index as u8).wrapping_sub(1)).collect();
+    assert_eq!(data.len(), expected.len());
+    assert!(data == expected);
+}
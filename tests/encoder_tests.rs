@@ -1,6 +1,9 @@
 // tests/encoder_tests.rs
 
-use app::encoder::write_vint;
+use app::decoder::read_checked_block_header;
+use app::encoder::{encode_block, write_vint};
+use app::structures::{base::BlockHeader, block::Block, extra_record::ExtraRecord, file_header::FileHeader};
+use std::io::{Cursor, Read};
 
 #[test]
 fn test_write_vint() {
@@ -23,3 +26,39 @@ fn test_write_vint() {
     write_vint(&mut buffer, 0x4000).unwrap();
     assert_eq!(buffer, vec![0x80, 0x80, 0x01]);
 }
+
+#[test]
+fn test_encode_block_writes_header_crc32() {
+    let file_header = FileHeader {
+        file_name: "crc.txt".to_string(),
+        ..Default::default()
+    };
+    let mut buffer = Vec::new();
+    file_header.encode(&mut buffer).unwrap();
+
+    let (general_header, _) = read_checked_block_header(&mut Cursor::new(buffer)).unwrap();
+    assert_ne!(general_header.crc32, 0);
+    assert!(!general_header.has_crc_mismatch());
+}
+
+#[test]
+fn test_raw_blocks_are_framed_like_typed_blocks() {
+    let mut header = FileHeader {
+        file_name: "framed.txt".to_string(),
+        data_crc32: Some(0x1234_5678),
+        ..Default::default()
+    };
+    header.base.general_header.data_size = Some(10);
+    header.set_extra_records(&[ExtraRecord::FileVersion { flags: 0, version: 2 }]).unwrap();
+    let mut typed = Vec::new();
+    header.encode(&mut typed).unwrap();
+
+    // The same block kept as the general header plus the raw bytes after it.
+    let mut reader = Cursor::new(typed.clone());
+    let (general_header, mut rest) = read_checked_block_header(&mut reader).unwrap();
+    let mut raw = Vec::new();
+    rest.read_to_end(&mut raw).unwrap();
+    let mut framed = Vec::new();
+    encode_block(&BlockHeader::Unknown { general_header, raw }, &mut framed).unwrap();
+    assert_eq!(framed, typed);
+}
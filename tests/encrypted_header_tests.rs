@@ -0,0 +1,61 @@
+// tests/encrypted_header_tests.rs
+
+use app::archive::Archive;
+use app::structures::base::BlockHeader;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind};
+
+/// An archive whose headers are encrypted with the password "password"
+/// (2^15 KDF iterations). It holds one stored entry, `secret.txt`.
+///
+/// Not made by rar: `tests/assets/scripts/encrypted_headers.py` writes it
+/// from the RAR 5.0 technote, independently of this crate, with
+/// `python3 tests/assets/scripts/encrypted_headers.py`. It should be replaced
+/// by the output of `rar a -hp"password" encrypted_headers.rar secret.txt`.
+const ENCRYPTED_HEADERS: &str = "tests/assets/encrypted_headers.rar";
+
+fn open_archive() -> Archive<BufReader<File>> {
+    Archive::new(BufReader::new(File::open(ENCRYPTED_HEADERS).unwrap())).unwrap()
+}
+
+#[test]
+fn test_lists_and_extracts_with_correct_password() {
+    let mut archive = open_archive();
+    archive.set_password("password");
+
+    match archive.next() {
+        Some(Ok(BlockHeader::Encryption(header))) => {
+            assert_eq!(header.kdf_count, 15);
+            assert!(header.check_value.is_some());
+        }
+        other => panic!("Expected an EncryptionHeader, but got {:?}", other),
+    }
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::Main(_)))));
+    match archive.next() {
+        Some(Ok(BlockHeader::File(header))) => {
+            assert_eq!(header.file_name, "secret.txt");
+            let data = archive.read_file_data(&header).unwrap();
+            assert_eq!(data, b"Encrypted headers hide this name.\n");
+        }
+        other => panic!("Expected a FileHeader, but got {:?}", other),
+    }
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::End(_)))));
+    assert!(archive.next().is_none());
+}
+
+#[test]
+fn test_wrong_password_is_rejected() {
+    let mut archive = open_archive();
+    archive.set_password("wrong");
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("Incorrect password"));
+}
+
+#[test]
+fn test_missing_password_is_reported() {
+    let mut archive = open_archive();
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("password is required"));
+}
@@ -0,0 +1,147 @@
+// tests/entry_reader_tests.rs
+
+use app::archive::Archive;
+use app::checksum::crc32;
+use app::signature::RAR5_SIGNATURE;
+use app::structures::{
+    base::BlockHeader,
+    block::{BaseBlock, Block},
+    file_header::FileHeader,
+    general_block_header::GeneralBlockHeader,
+};
+use app::volume::FileVolumes;
+use std::fs::File;
+use std::io::{BufReader, Cursor, ErrorKind, Read};
+
+fn next_file<R: Read>(archive: &mut Archive<R>) -> Option<FileHeader> {
+    loop {
+        match archive.next()? {
+            Ok(BlockHeader::File(header)) => return Some(header),
+            Ok(_) => continue,
+            Err(e) => panic!("Failed to read block: {}", e),
+        }
+    }
+}
+
+/// Reads everything through buffers of `chunk_size` bytes.
+fn read_in_chunks(reader: &mut impl Read, chunk_size: usize) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut buffer = vec![0u8; chunk_size];
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            return Ok(data);
+        }
+        assert!(count <= chunk_size);
+        data.extend_from_slice(&buffer[..count]);
+    }
+}
+
+/// An archive holding one stored entry with the given header fields.
+fn stored_entry(data_size: u64, data_crc32: Option<u32>, data: &[u8]) -> Vec<u8> {
+    let header = FileHeader {
+        base: BaseBlock {
+            general_header: GeneralBlockHeader {
+                data_size: Some(data_size),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        unpacked_size: Some(data.len() as u64),
+        data_crc32,
+        file_name: "entry.txt".to_string(),
+        ..Default::default()
+    };
+    let mut archive = RAR5_SIGNATURE.to_vec();
+    header.encode(&mut archive).unwrap();
+    archive.extend_from_slice(data);
+    archive
+}
+
+#[test]
+fn test_streams_the_same_data_as_read_file_data() {
+    let open = || Archive::new(BufReader::new(File::open("Rar5.rar").unwrap())).unwrap();
+    let mut whole = open();
+    let mut streamed = open();
+
+    let mut entries = 0;
+    while let Some(header) = next_file(&mut whole) {
+        let expected = whole.read_file_data(&header).unwrap();
+        let streamed_header = next_file(&mut streamed).unwrap();
+        assert_eq!(streamed_header.file_name, header.file_name);
+        let mut reader = streamed.entry_reader(&streamed_header).unwrap();
+        assert_eq!(read_in_chunks(&mut reader, 100).unwrap(), expected, "{}", header.file_name);
+        entries += 1;
+    }
+    assert!(entries > 0);
+}
+
+#[test]
+fn test_streams_encrypted_entries() {
+    let file = File::open("tests/assets/encrypted_files.rar").unwrap();
+    let mut archive = Archive::new(BufReader::new(file)).unwrap();
+    archive.set_password("password");
+
+    let stored = next_file(&mut archive).unwrap();
+    let data = read_in_chunks(&mut archive.entry_reader(&stored).unwrap(), 7).unwrap();
+    assert_eq!(data, b"Only the right password reveals this.\n");
+
+    let compressed = next_file(&mut archive).unwrap();
+    let data = read_in_chunks(&mut archive.entry_reader(&compressed).unwrap(), 1000).unwrap();
+    assert_eq!(data.len(), 15498);
+    assert_eq!(crc32(&data), 0x9bd160fa);
+}
+
+#[test]
+fn test_streams_data_split_across_volumes() {
+    let mut archive = Archive::from_volumes(FileVolumes::new("tests/assets/multi.part1.rar")).unwrap();
+    next_file(&mut archive).unwrap();
+    let split = next_file(&mut archive).unwrap();
+    assert_eq!(split.file_name, "split.bin");
+
+    let data = read_in_chunks(&mut archive.entry_reader(&split).unwrap(), 512).unwrap();
+    assert_eq!(data.len(), 15498);
+    assert_eq!(crc32(&data), 0x9bd160fa);
+
+    let last = next_file(&mut archive).unwrap();
+    assert_eq!(archive.read_file_data(&last).unwrap(), b"The last entry follows the split one.\n");
+}
+
+#[test]
+fn test_dropped_reader_leaves_the_rest_to_be_skipped() {
+    let mut archive = Archive::new(BufReader::new(File::open("Rar5.rar").unwrap())).unwrap();
+    let first = next_file(&mut archive).unwrap();
+    let mut buffer = [0u8; 16];
+    let _ = archive.entry_reader(&first).unwrap().read(&mut buffer).unwrap();
+
+    let mut names = vec![first.file_name];
+    while let Some(header) = next_file(&mut archive) {
+        names.push(header.file_name);
+    }
+    assert!(names.len() > 1, "{:?}", names);
+}
+
+#[test]
+fn test_checksum_mismatch_is_reported_at_the_end() {
+    let data = b"Streamed before the checksum is known.\n";
+    let mut archive = Archive::new(Cursor::new(stored_entry(data.len() as u64, Some(0xDEADBEEF), data))).unwrap();
+    let header = next_file(&mut archive).unwrap();
+
+    let mut reader = archive.entry_reader(&header).unwrap();
+    let mut buffer = [0u8; 8];
+    assert_eq!(reader.read(&mut buffer).unwrap(), 8);
+    assert_eq!(&buffer, b"Streamed");
+    let error = read_in_chunks(&mut reader, 8).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_huge_data_size_is_not_allocated_up_front() {
+    let data = b"short";
+    let mut archive = Archive::new(Cursor::new(stored_entry(1 << 50, None, data))).unwrap();
+    let header = next_file(&mut archive).unwrap();
+
+    let mut reader = archive.entry_reader(&header).unwrap();
+    let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,124 @@
+// tests/extra_record_tests.rs
+
+use app::archive::Archive;
+use app::checksum::crc32;
+use app::signature::RAR5_SIGNATURE;
+use app::structures::{
+    base::BlockHeader,
+    block::{BaseBlock, Block},
+    extra_area::ExtraAreaRecord,
+    extra_record::{ExtraRecord, FILE_VERSION, SERVICE_DATA},
+    file_header::FileHeader,
+    general_block_header::GeneralBlockHeader,
+};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+
+fn first_file_header(path: &str) -> FileHeader {
+    let file = File::open(path).unwrap();
+    let archive = Archive::new(BufReader::new(file)).unwrap();
+    for block in archive {
+        if let BlockHeader::File(header) = block.unwrap() {
+            return header;
+        }
+    }
+    panic!("No file header in {}", path);
+}
+
+#[test]
+fn test_file_version_record_round_trips() {
+    let raw = ExtraAreaRecord { kind: FILE_VERSION, data: vec![0x00, 0x03] };
+    let record = ExtraRecord::decode(&raw).unwrap();
+    assert_eq!(record, ExtraRecord::FileVersion { flags: 0, version: 3 });
+    assert_eq!(record.kind(), FILE_VERSION);
+    assert_eq!(record.encode().unwrap(), raw);
+}
+
+#[test]
+fn test_service_data_record_round_trips() {
+    let raw = ExtraAreaRecord { kind: SERVICE_DATA, data: vec![4, 5, 6] };
+    let record = ExtraRecord::decode(&raw).unwrap();
+    assert_eq!(record, ExtraRecord::ServiceData(vec![4, 5, 6]));
+    assert_eq!(record.kind(), SERVICE_DATA);
+    assert_eq!(record.encode().unwrap(), raw);
+}
+
+#[test]
+fn test_unrecognised_record_keeps_raw_bytes() {
+    let raw = ExtraAreaRecord { kind: 0x42, data: vec![1, 2, 3] };
+    let record = ExtraRecord::decode(&raw).unwrap();
+    assert_eq!(record, ExtraRecord::Unknown { kind: 0x42, data: vec![1, 2, 3] });
+    assert_eq!(record.encode().unwrap(), raw);
+}
+
+#[test]
+fn test_extra_records_from_winrar_header() {
+    let header = first_file_header("tests/assets/uncompressed.rar");
+    let records = header.extra_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].kind(), 0x03);
+}
+
+#[test]
+fn test_set_extra_records_round_trips_through_encode() {
+    let mut header = first_file_header("tests/assets/uncompressed.rar");
+    let mut records = header.extra_records();
+    records.push(ExtraRecord::FileVersion { flags: 0, version: 7 });
+    records.push(ExtraRecord::Unknown { kind: 0x42, data: vec![9] });
+    header.set_extra_records(&records).unwrap();
+
+    let mut encoded = RAR5_SIGNATURE.to_vec();
+    header.encode(&mut encoded).unwrap();
+    let mut archive = Archive::new(Cursor::new(encoded)).unwrap();
+    match archive.next() {
+        Some(Ok(BlockHeader::File(decoded))) => {
+            assert_eq!(decoded.extra_records(), records);
+            assert_eq!(decoded.file_name, header.file_name);
+        }
+        other => panic!("Expected a FileHeader, but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_malformed_record_does_not_hide_other_records() {
+    let data = b"Readable despite a broken time record.\n";
+    let mut header = FileHeader {
+        base: BaseBlock {
+            general_header: GeneralBlockHeader {
+                data_size: Some(data.len() as u64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        unpacked_size: Some(data.len() as u64),
+        data_crc32: Some(crc32(data)),
+        file_name: "entry.txt".to_string(),
+        ..Default::default()
+    };
+    // A file time record announcing an mtime it does not contain, and a
+    // version record after it.
+    header.base.extra_area = vec![
+        ExtraAreaRecord { kind: 0x03, data: vec![0x02] },
+        ExtraAreaRecord { kind: FILE_VERSION, data: vec![0x00, 0x03] },
+    ];
+    assert!(header.file_time().is_err());
+    assert_eq!(
+        header.extra_records(),
+        [
+            ExtraRecord::Unknown { kind: 0x03, data: vec![0x02] },
+            ExtraRecord::FileVersion { flags: 0, version: 3 },
+        ]
+    );
+    assert_eq!(header.file_hash().unwrap(), None);
+    assert_eq!(header.encryption().unwrap(), None);
+    assert_eq!(header.redirection().unwrap(), None);
+
+    let mut encoded = RAR5_SIGNATURE.to_vec();
+    header.encode(&mut encoded).unwrap();
+    encoded.extend_from_slice(data);
+    let mut archive = Archive::new(Cursor::new(encoded)).unwrap();
+    let Some(Ok(BlockHeader::File(decoded))) = archive.next() else {
+        panic!("Expected a FileHeader");
+    };
+    assert_eq!(archive.read_file_data(&decoded).unwrap(), data);
+}
@@ -0,0 +1,102 @@
+// tests/file_encryption_tests.rs
+
+use app::archive::Archive;
+use app::structures::base::BlockHeader;
+use app::structures::extra_record::ExtraRecord;
+use app::structures::file_encryption::FileEncryption;
+use app::structures::file_header::FileHeader;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind};
+
+/// An archive with plain headers whose two entries are encrypted with the
+/// password "password" (2^15 KDF iterations) and store their CRC32 as a MAC:
+/// `stored.txt` and `compressed.txt`, a compressed entry from Rar5.rar.
+///
+/// Not made by rar: `tests/assets/scripts/encrypted_files.py` writes it from
+/// the RAR 5.0 technote, independently of this crate, with
+/// `python3 tests/assets/scripts/encrypted_files.py`; only the packed data of
+/// `compressed.txt` comes from rar. libarchive (`bsdtar -tvf`) lists both
+/// entries. It should be replaced by the output of
+/// `rar a -p"password" -htc encrypted_files.rar stored.txt compressed.txt`.
+const ENCRYPTED_FILES: &str = "tests/assets/encrypted_files.rar";
+
+fn open_archive() -> Archive<BufReader<File>> {
+    Archive::new(BufReader::new(File::open(ENCRYPTED_FILES).unwrap())).unwrap()
+}
+
+fn next_file(archive: &mut Archive<BufReader<File>>) -> FileHeader {
+    loop {
+        match archive.next() {
+            Some(Ok(BlockHeader::File(header))) => return header,
+            Some(Ok(_)) => continue,
+            other => panic!("Expected a FileHeader, but got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_decrypts_entries_with_correct_password() {
+    let mut archive = open_archive();
+    archive.set_password("password");
+
+    let stored = next_file(&mut archive);
+    assert_eq!(stored.file_name, "stored.txt");
+    let encryption = stored.encryption().unwrap().unwrap();
+    assert_eq!(encryption.kdf_count, 15);
+    assert!(encryption.use_mac);
+    assert!(encryption.check_value.is_some());
+    let data = archive.read_file_data(&stored).unwrap();
+    assert_eq!(data, b"Only the right password reveals this.\n");
+
+    let compressed = next_file(&mut archive);
+    assert_eq!(compressed.file_name, "compressed.txt");
+    let data = archive.read_file_data(&compressed).unwrap();
+    assert_eq!(data.len(), 15498);
+    assert_eq!(app::checksum::crc32(&data), 0x9bd160fa);
+}
+
+#[test]
+fn test_wrong_password_is_rejected() {
+    let mut archive = open_archive();
+    archive.set_password("wrong");
+    let header = next_file(&mut archive);
+    let error = archive.read_file_data(&header).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("Incorrect password"));
+}
+
+#[test]
+fn test_missing_password_is_reported() {
+    let mut archive = open_archive();
+    let header = next_file(&mut archive);
+    let error = archive.read_file_data(&header).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("password is required"));
+}
+
+#[test]
+fn test_skipping_an_encrypted_entry_keeps_the_archive_readable() {
+    let mut archive = open_archive();
+    next_file(&mut archive);
+    let header = next_file(&mut archive);
+    assert_eq!(header.file_name, "compressed.txt");
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::End(_)))));
+}
+
+#[test]
+fn test_encryption_record_round_trip() {
+    let record = FileEncryption {
+        version: 0,
+        use_mac: true,
+        kdf_count: 15,
+        salt: [7; 16],
+        iv: [9; 16],
+        check_value: Some([3; 12]),
+    };
+    let encoded = ExtraRecord::FileEncryption(record.clone()).encode().unwrap();
+    assert_eq!(encoded.kind, 0x01);
+    assert_eq!(ExtraRecord::decode(&encoded).unwrap(), ExtraRecord::FileEncryption(record));
+
+    let without_check = FileEncryption { check_value: None, use_mac: false, ..Default::default() };
+    assert_eq!(FileEncryption::decode(&without_check.encode().unwrap()).unwrap(), without_check);
+}
@@ -0,0 +1,85 @@
+// tests/file_hash_tests.rs
+
+use app::archive::Archive;
+use app::blake2sp::blake2sp;
+use app::integrity::IntegrityError;
+use app::signature::RAR5_SIGNATURE;
+use app::structures::{
+    base::BlockHeader,
+    block::{BaseBlock, Block},
+    extra_record::ExtraRecord,
+    file_hash::FileHash,
+    file_header::FileHeader,
+    general_block_header::GeneralBlockHeader,
+};
+use std::io::{Cursor, ErrorKind};
+
+fn archive_with_hash(name: &str, data: &[u8], hash: FileHash) -> Archive<Cursor<Vec<u8>>> {
+    let mut file_header = FileHeader {
+        base: BaseBlock {
+            general_header: GeneralBlockHeader {
+                data_size: Some(data.len() as u64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        file_name: name.to_string(),
+        ..Default::default()
+    };
+    file_header.set_extra_records(&[ExtraRecord::FileHash(hash)]).unwrap();
+
+    let mut archive = RAR5_SIGNATURE.to_vec();
+    file_header.encode(&mut archive).unwrap();
+    archive.extend_from_slice(data);
+    Archive::new(Cursor::new(archive)).unwrap()
+}
+
+fn read_first_entry(archive: &mut Archive<Cursor<Vec<u8>>>) -> std::io::Result<Vec<u8>> {
+    match archive.next() {
+        Some(Ok(BlockHeader::File(header))) => archive.read_file_data(&header),
+        other => panic!("Expected a FileHeader, but got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hash_record_round_trips() {
+    let mut data = vec![0x00];
+    data.extend_from_slice(&[0xAB; 32]);
+    let file_hash = FileHash::decode(&data).unwrap();
+    assert_eq!(file_hash, FileHash::Blake2sp([0xAB; 32]));
+    assert_eq!(file_hash.encode().unwrap(), data);
+}
+
+#[test]
+fn test_unknown_hash_type_is_kept() {
+    let data = [0x05, 1, 2, 3];
+    let file_hash = FileHash::decode(&data).unwrap();
+    assert_eq!(file_hash, FileHash::Unknown { hash_type: 5, data: vec![1, 2, 3] });
+    assert_eq!(file_hash.encode().unwrap(), data);
+}
+
+#[test]
+fn test_matching_hash_is_accepted() {
+    let data = b"hashed contents";
+    let mut archive = archive_with_hash("good.txt", data, FileHash::Blake2sp(blake2sp(data)));
+    assert_eq!(read_first_entry(&mut archive).unwrap(), data);
+}
+
+#[test]
+fn test_hash_mismatch_names_the_entry() {
+    let data = b"hashed contents";
+    let mut archive = archive_with_hash("bad.txt", data, FileHash::Blake2sp([0; 32]));
+    let error = read_first_entry(&mut archive).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let integrity_error = error.get_ref().unwrap().downcast_ref::<IntegrityError>().unwrap();
+    match integrity_error {
+        IntegrityError::HashMismatch { expected, actual, name } => {
+            assert_eq!(name, "bad.txt");
+            assert_eq!(*expected, [0; 32]);
+            assert_eq!(*actual, blake2sp(data));
+        }
+        other => panic!("Expected a HashMismatch, but got {:?}", other),
+    }
+    assert!(error.to_string().contains("bad.txt"));
+}
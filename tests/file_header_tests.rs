@@ -0,0 +1,111 @@
+// tests/file_header_tests.rs
+
+use app::structures::{block::Block, compression_info::CompressionInfo, file_header::FileHeader};
+use std::io::Cursor;
+
+/// The type-specific fields of the file header in tests/assets/uncompressed.rar,
+/// written by WinRAR on Unix.
+const UNCOMPRESSED_TXT_FIELDS: [u8; 35] = [
+    0x04, // File flags: data CRC32 present
+    0xA3, 0x00, // Unpacked size: 35
+    0xA4, 0x83, 0x02, // Attributes: 0o100644
+    0x02, 0x86, 0xA5, 0x73, // Data CRC32
+    0x80, 0x00, // Compression info: stored
+    0x01, // Host OS: Unix
+    0x15, // Name length: 21
+    0x75, 0x6E, 0x63, 0x6F, 0x6D, 0x70, 0x72, 0x65, 0x73, 0x73, 0x65, 0x64, 0x5F, 0x74, 0x65,
+    0x73, 0x74, 0x2E, 0x74, 0x78, 0x74,
+];
+
+/// The type-specific fields of the `exe/test.exe` header in Rar5.rar, written
+/// by WinRAR on Windows.
+const TEST_EXE_FIELDS: [u8; 25] = [
+    0x04, // File flags: data CRC32 present
+    0x80, 0xE0, 0x02, // Unpacked size: 45056
+    0x20, // Attributes: FILE_ATTRIBUTE_ARCHIVE
+    0xC8, 0x09, 0xB1, 0xCF, // Data CRC32
+    0x80, 0x03, // Compression info: method 3
+    0x00, // Host OS: Windows
+    0x0C, // Name length: 12
+    0x65, 0x78, 0x65, 0x2F, 0x74, 0x65, 0x73, 0x74, 0x2E, 0x65, 0x78, 0x65,
+];
+
+fn decode_fields(fields: &[u8]) -> FileHeader {
+    let mut header = FileHeader::default();
+    header.decode_data(&mut Cursor::new(fields)).unwrap();
+    header
+}
+
+#[test]
+fn test_decode_unix_file_header() {
+    let header = decode_fields(&UNCOMPRESSED_TXT_FIELDS);
+
+    assert_eq!(header.file_flags, 0x04);
+    assert_eq!(header.unpacked_size, Some(35));
+    assert_eq!(header.attributes, 0o100644);
+    assert_eq!(header.mtime, None);
+    assert_eq!(header.data_crc32, Some(0x73A58602));
+    assert_eq!(header.compression_info, CompressionInfo::new(0));
+    assert_eq!(header.host_os, 1);
+    assert_eq!(header.file_name, "uncompressed_test.txt");
+    assert!(!header.is_directory());
+}
+
+#[test]
+fn test_decode_windows_file_header() {
+    let header = decode_fields(&TEST_EXE_FIELDS);
+
+    assert_eq!(header.unpacked_size, Some(45056));
+    assert_eq!(header.attributes, 0x20);
+    assert_eq!(header.data_crc32, Some(0xCFB109C8));
+    assert_eq!(header.compression_info.method(), 3);
+    assert_eq!(header.host_os, 0);
+    assert_eq!(header.file_name, "exe/test.exe");
+}
+
+#[test]
+fn test_winrar_file_headers_round_trip() {
+    for fields in [&UNCOMPRESSED_TXT_FIELDS[..], &TEST_EXE_FIELDS[..]] {
+        let header = decode_fields(fields);
+        let mut encoded = Vec::new();
+        header.encode_data(&mut encoded).unwrap();
+        assert_eq!(decode_fields(&encoded), header);
+    }
+}
+
+#[test]
+fn test_optional_fields_round_trip() {
+    let header = FileHeader {
+        file_flags: 0x0001,
+        unpacked_size: None,
+        mtime: Some(0x5F5E_1000),
+        data_crc32: Some(0xDEAD_BEEF),
+        file_name: "dir".to_string(),
+        ..Default::default()
+    };
+    let mut encoded = Vec::new();
+    header.encode_data(&mut encoded).unwrap();
+
+    let decoded = decode_fields(&encoded);
+    assert_eq!(decoded.file_flags, 0x000F);
+    assert_eq!(decoded.unpacked_size, None);
+    assert_eq!(decoded.mtime, Some(0x5F5E_1000));
+    assert_eq!(decoded.data_crc32, Some(0xDEAD_BEEF));
+    assert!(decoded.is_directory());
+}
+
+#[test]
+fn test_oversized_name_length_is_rejected() {
+    let fields = [
+        0x00, // File flags
+        0x03, // Unpacked size
+        0x20, // Attributes
+        0x00, // Compression info
+        0x01, // Host OS: Unix
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F, // Name length: 2^63 - 1
+        0x61, 0x62, 0x63,
+    ];
+    let mut header = FileHeader::default();
+    let error = header.decode_data(&mut Cursor::new(&fields[..])).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,193 @@
+// tests/file_time_tests.rs
+
+use app::archive::Archive;
+use app::extract::Extractor;
+use app::structures::{
+    base::BlockHeader,
+    extra_area::ExtraAreaRecord,
+    extra_record::ExtraRecord,
+    file_header::FileHeader,
+    file_time::{FileTimeRecord, TimeFormat},
+};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn file_headers(path: &str) -> Vec<FileHeader> {
+    let archive = Archive::new(BufReader::new(File::open(path).unwrap())).unwrap();
+    // Stops at the first block the reader cannot decode.
+    archive
+        .map_while(Result::ok)
+        .filter_map(|block| match block {
+            BlockHeader::File(header) => Some(header),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_decode_unix_time_with_nanoseconds() {
+    let headers = file_headers("tests/assets/uncompressed.rar");
+    let file_time = headers[0].file_time().unwrap().unwrap();
+    assert_eq!(file_time.format, TimeFormat::UnixNanoseconds);
+    assert_eq!(
+        file_time.mtime,
+        Some(UNIX_EPOCH + Duration::new(0x689E_ACA0, 0x2207_5B59))
+    );
+    assert_eq!(file_time.ctime, None);
+    assert_eq!(file_time.atime, None);
+}
+
+#[test]
+fn test_out_of_range_nanoseconds_are_dropped() {
+    // Unix times with nanoseconds: an mtime whose nanosecond part is too large
+    // and an atime whose part is valid.
+    let mut data = vec![0x01 | 0x02 | 0x08 | 0x10];
+    data.extend_from_slice(&1_600_000_000u32.to_le_bytes());
+    data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+    data.extend_from_slice(&1_000_000_000u32.to_le_bytes());
+    data.extend_from_slice(&500u32.to_le_bytes());
+
+    let record = FileTimeRecord::decode(&data).unwrap();
+    assert_eq!(record.mtime, Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000)));
+    assert_eq!(record.atime, Some(UNIX_EPOCH + Duration::new(1_700_000_000, 500)));
+
+    let mut header = FileHeader::default();
+    header.base.extra_area = vec![ExtraAreaRecord { kind: 0x03, data }];
+    assert_eq!(header.file_time().unwrap(), Some(record));
+}
+
+#[test]
+fn test_reserved_nanosecond_bits_are_ignored() {
+    let mut data = vec![0x01 | 0x02 | 0x10];
+    data.extend_from_slice(&1_600_000_000u32.to_le_bytes());
+    data.extend_from_slice(&(0x8000_0000u32 | 250).to_le_bytes());
+
+    let record = FileTimeRecord::decode(&data).unwrap();
+    assert_eq!(record.mtime, Some(UNIX_EPOCH + Duration::new(1_600_000_000, 250)));
+}
+
+#[test]
+fn test_decode_windows_filetime() {
+    let headers = file_headers("Rar5.rar");
+    let file_time = headers[0].file_time().unwrap().unwrap();
+    assert_eq!(file_time.format, TimeFormat::WindowsFileTime);
+    let mtime = file_time.mtime.unwrap();
+    // FILETIME has 100 ns precision.
+    assert_eq!(mtime.duration_since(UNIX_EPOCH).unwrap().subsec_nanos() % 100, 0);
+}
+
+#[test]
+fn test_filetime_epoch_conversion() {
+    // 116444736000000000 ticks since 1601 is the Unix epoch.
+    let mut data = vec![0x02];
+    data.extend_from_slice(&116_444_736_000_000_000u64.to_le_bytes());
+    let file_time = FileTimeRecord::decode(&data).unwrap();
+    assert_eq!(file_time.mtime, Some(UNIX_EPOCH));
+    assert_eq!(file_time.encode().unwrap(), data);
+}
+
+#[test]
+fn test_all_times_round_trip_in_each_format() {
+    let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_700);
+    for format in [TimeFormat::WindowsFileTime, TimeFormat::Unix, TimeFormat::UnixNanoseconds] {
+        let record = FileTimeRecord {
+            format,
+            mtime: Some(time),
+            ctime: Some(time + Duration::from_secs(1)),
+            atime: Some(time + Duration::from_secs(2)),
+        };
+        let decoded = FileTimeRecord::decode(&record.encode().unwrap()).unwrap();
+        if format == TimeFormat::Unix {
+            assert_eq!(decoded.mtime, Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+            assert_eq!(decoded.atime, Some(UNIX_EPOCH + Duration::from_secs(1_700_000_002)));
+        } else {
+            assert_eq!(decoded, record);
+        }
+    }
+}
+
+#[test]
+fn test_file_time_record_round_trips_through_header() {
+    let mut header = file_headers("tests/assets/uncompressed.rar").remove(0);
+    let records = header.extra_records();
+    assert!(matches!(records[0], ExtraRecord::FileTime(_)));
+    let raw = header.base.extra_area.clone();
+    header.set_extra_records(&records).unwrap();
+    assert_eq!(header.base.extra_area, raw);
+}
+
+#[test]
+fn test_extractor_applies_modification_time() {
+    let header = file_headers("tests/assets/uncompressed.rar").remove(0);
+    let dir = tempfile::tempdir().unwrap();
+    let path = Extractor::new(dir.path()).extract(&header, b"content").unwrap();
+
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    assert_eq!(Some(modified), header.file_time().unwrap().unwrap().mtime);
+    assert_eq!(fs::read(&path).unwrap(), b"content");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extractor_sets_times_on_symlinks_not_their_targets() {
+    use app::structures::redirection::{Redirection, RedirectionKind};
+
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    let target = FileHeader {
+        file_name: "target.txt".to_string(),
+        mtime: Some(1_000_000_000),
+        ..Default::default()
+    };
+    let target_path = extractor.extract(&target, b"target").unwrap();
+
+    let mut link = FileHeader {
+        file_name: "link".to_string(),
+        mtime: Some(1_500_000_000),
+        ..Default::default()
+    };
+    let redirection = Redirection {
+        kind: RedirectionKind::UnixSymlink,
+        target: "target.txt".to_string(),
+        is_directory: false,
+    };
+    link.set_extra_records(&[ExtraRecord::Redirection(redirection)]).unwrap();
+    let link_path = extractor.extract(&link, b"").unwrap();
+
+    let link_modified = fs::symlink_metadata(&link_path).unwrap().modified().unwrap();
+    assert_eq!(link_modified, UNIX_EPOCH + Duration::from_secs(1_500_000_000));
+    let target_modified = fs::metadata(&target_path).unwrap().modified().unwrap();
+    assert_eq!(target_modified, UNIX_EPOCH + Duration::from_secs(1_000_000_000));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_apply_file_times_does_not_need_read_access() {
+    use app::extract::apply_file_times;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("locked.txt");
+    fs::write(&path, b"locked").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o000)).unwrap();
+
+    let header = FileHeader {
+        mtime: Some(1_234_567_890),
+        ..Default::default()
+    };
+    apply_file_times(&path, &header).unwrap();
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    assert_eq!(modified, UNIX_EPOCH + Duration::from_secs(1_234_567_890));
+}
+
+#[test]
+fn test_extractor_refuses_paths_outside_destination() {
+    let header = FileHeader {
+        file_name: "../escape.txt".to_string(),
+        ..Default::default()
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let error = Extractor::new(dir.path()).extract(&header, b"").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
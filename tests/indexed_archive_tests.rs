@@ -0,0 +1,94 @@
+// tests/indexed_archive_tests.rs
+
+use app::archive::Archive;
+use app::indexed_archive::IndexedArchive;
+use app::structures::base::BlockHeader;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::rc::Rc;
+
+/// Counts the bytes read through it, to show data areas are seeked past.
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.bytes_read.set(self.bytes_read.get() + count as u64);
+        Ok(count)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// Reads every file entry of an archive front to back.
+fn stream_entries(path: &str) -> Vec<(String, Vec<u8>)> {
+    let mut archive = Archive::new(BufReader::new(File::open(path).unwrap())).unwrap();
+    let mut entries = Vec::new();
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            let data = archive.read_file_data(&header).unwrap();
+            entries.push((header.file_name, data));
+        }
+    }
+    entries
+}
+
+#[test]
+fn test_index_scan_seeks_past_data() {
+    let file = File::open("Rar5.rar").unwrap();
+    let file_size = file.metadata().unwrap().len();
+    let bytes_read = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        inner: file,
+        bytes_read: Rc::clone(&bytes_read),
+    };
+    let archive = IndexedArchive::new(reader).unwrap();
+
+    assert_eq!(archive.entries().len(), 6);
+    let data_size: u64 = archive
+        .entries()
+        .iter()
+        .filter_map(|entry| entry.header.base.general_header.data_size)
+        .sum();
+    assert!(data_size > file_size / 2);
+    assert!(bytes_read.get() < file_size - data_size);
+}
+
+#[test]
+fn test_random_access_matches_streaming() {
+    let streamed = stream_entries("Rar5.rar");
+    let file = BufReader::new(File::open("Rar5.rar").unwrap());
+    let mut archive = IndexedArchive::new(file).unwrap();
+
+    for (index, (name, data)) in streamed.iter().enumerate().rev() {
+        assert_eq!(archive.entries()[index].header.file_name, *name);
+        assert_eq!(archive.extract_by_index(index).unwrap(), *data, "{}", name);
+        assert_eq!(archive.extract_by_name(name).unwrap(), *data, "{}", name);
+    }
+}
+
+#[test]
+fn test_index_of_encrypted_archive() {
+    let file = BufReader::new(File::open("tests/assets/encrypted_headers.rar").unwrap());
+    let mut archive = Archive::new(file).unwrap();
+    archive.set_password("password");
+    let mut archive = IndexedArchive::from_archive(archive).unwrap();
+    assert_eq!(archive.position("secret.txt"), Some(0));
+    assert_eq!(archive.extract_by_name("secret.txt").unwrap(), b"Encrypted headers hide this name.\n");
+}
+
+#[test]
+fn test_unknown_entries_are_reported() {
+    let file = BufReader::new(File::open("Rar5.rar").unwrap());
+    let mut archive = IndexedArchive::new(file).unwrap();
+    assert_eq!(archive.extract_by_name("missing.txt").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(archive.extract_by_index(6).unwrap_err().kind(), ErrorKind::NotFound);
+}
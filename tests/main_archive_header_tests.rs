@@ -0,0 +1,96 @@
+// tests/main_archive_header_tests.rs
+
+use app::archive::Archive;
+use app::decoder::read_general_block_header;
+use app::structures::{
+    base::BlockHeader,
+    block::Block,
+    extra_area::ExtraAreaRecord,
+    locator::Locator,
+    main_archive_header::MainArchiveHeader,
+};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Seek, SeekFrom};
+
+/// Where the main archive header of a RAR5 archive starts.
+const MAIN_HEADER_OFFSET: u64 = 8;
+
+fn round_trip(header: &mut MainArchiveHeader) -> MainArchiveHeader {
+    let mut buffer = Vec::new();
+    header.encode(&mut buffer).unwrap();
+    let mut reader = Cursor::new(buffer);
+    let general_header = read_general_block_header(&mut reader).unwrap();
+    MainArchiveHeader::decode(general_header, &mut reader).unwrap()
+}
+
+#[test]
+fn test_rar5_main_header_locates_quick_open_block() {
+    let file = File::open("Rar5.rar").unwrap();
+    let mut archive = Archive::new(BufReader::new(file)).unwrap();
+    let Some(Ok(BlockHeader::Main(header))) = archive.next() else {
+        panic!("Expected a MainArchiveHeader");
+    };
+    assert!(!header.is_volume());
+    assert!(!header.is_solid());
+    assert!(!header.has_recovery_record());
+    assert!(!header.is_locked());
+    assert_eq!(header.volume_number, None);
+
+    let locator = header.locator().unwrap().unwrap();
+    assert_eq!(locator.recovery_offset, None);
+    let quick_open_offset = locator.quick_open_offset.unwrap();
+
+    let mut file = File::open("Rar5.rar").unwrap();
+    file.seek(SeekFrom::Start(MAIN_HEADER_OFFSET + quick_open_offset)).unwrap();
+    let general_header = read_general_block_header(&mut file).unwrap();
+    assert_eq!(general_header.header_type, 3);
+}
+
+#[test]
+fn test_flags_and_volume_number_round_trip() {
+    let mut header = MainArchiveHeader {
+        archive_flags: 0x0001 | 0x0004 | 0x0008 | 0x0010,
+        volume_number: Some(3),
+        ..Default::default()
+    };
+    let decoded = round_trip(&mut header);
+    assert!(decoded.is_volume());
+    assert!(decoded.is_solid());
+    assert!(decoded.has_recovery_record());
+    assert!(decoded.is_locked());
+    assert_eq!(decoded.volume_number, Some(3));
+    assert_eq!(decoded.archive_flags, 0x001F);
+}
+
+#[test]
+fn test_volume_number_flag_follows_the_field() {
+    let mut header = MainArchiveHeader {
+        archive_flags: 0x0001 | 0x0002,
+        volume_number: None,
+        ..Default::default()
+    };
+    let decoded = round_trip(&mut header);
+    assert!(decoded.is_volume());
+    assert_eq!(decoded.archive_flags, 0x0001);
+    assert_eq!(decoded.volume_number, None);
+}
+
+#[test]
+fn test_locator_round_trip_keeps_other_records() {
+    let mut header = MainArchiveHeader::default();
+    header.base.extra_area.push(ExtraAreaRecord { kind: 0x7F, data: vec![1, 2] });
+    let locator = Locator {
+        quick_open_offset: Some(0x1234),
+        recovery_offset: Some(0x56789),
+    };
+    header.set_locator(Some(locator)).unwrap();
+
+    let mut decoded = round_trip(&mut header);
+    assert_eq!(decoded.locator().unwrap(), Some(locator));
+    assert_eq!(decoded.base.extra_area.len(), 2);
+
+    decoded.set_locator(None).unwrap();
+    let decoded = round_trip(&mut decoded);
+    assert_eq!(decoded.locator().unwrap(), None);
+    assert_eq!(decoded.base.extra_area, [ExtraAreaRecord { kind: 0x7F, data: vec![1, 2] }]);
+}
@@ -0,0 +1,138 @@
+// tests/password_provider_tests.rs
+
+use app::archive::Archive;
+use app::password::{PasswordCancelled, PasswordRequest, PasswordResponse, PasswordTarget};
+use app::structures::base::BlockHeader;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind};
+use std::rc::Rc;
+
+/// Headers encrypted with "password"; holds `secret.txt`.
+const ENCRYPTED_HEADERS: &str = "tests/assets/encrypted_headers.rar";
+/// Plain headers; `stored.txt` and `compressed.txt` are encrypted with
+/// "password" and share one salt.
+const ENCRYPTED_FILES: &str = "tests/assets/encrypted_files.rar";
+
+/// The targets and failure counts a provider was asked with.
+type Requests = Rc<RefCell<Vec<(String, u32)>>>;
+
+fn open_archive(path: &str) -> Archive<BufReader<File>> {
+    Archive::new(BufReader::new(File::open(path).unwrap())).unwrap()
+}
+
+/// Sets a provider that answers with `answers` in turn, cancelling once they
+/// run out, and returns the requests it receives.
+fn set_answers(archive: &mut Archive<BufReader<File>>, answers: &[&str]) -> Requests {
+    let requests = Requests::default();
+    let recorded = Rc::clone(&requests);
+    let mut answers: Vec<String> = answers.iter().rev().map(|answer| answer.to_string()).collect();
+    archive.set_password_provider(move |request: &PasswordRequest| {
+        recorded.borrow_mut().push((request.target.to_string(), request.failed_attempts));
+        match answers.pop() {
+            Some(answer) => PasswordResponse::Password(answer.into()),
+            None => PasswordResponse::Cancel,
+        }
+    });
+    requests
+}
+
+#[test]
+fn test_provider_supplies_header_password() {
+    let mut archive = open_archive(ENCRYPTED_HEADERS);
+    let requests = set_answers(&mut archive, &["password"]);
+
+    let names: Vec<String> = archive
+        .by_ref()
+        .map(|block| block.unwrap())
+        .filter_map(|block| match block {
+            BlockHeader::File(header) => Some(header.file_name),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, ["secret.txt"]);
+    assert_eq!(*requests.borrow(), [("the archive headers".to_string(), 0)]);
+}
+
+#[test]
+fn test_provider_is_asked_again_after_a_wrong_password() {
+    let mut archive = open_archive(ENCRYPTED_HEADERS);
+    let requests = set_answers(&mut archive, &["wrong", "also wrong", "password"]);
+
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::Encryption(_)))));
+    let failures: Vec<u32> = requests.borrow().iter().map(|(_, failures)| *failures).collect();
+    assert_eq!(failures, [0, 1, 2]);
+}
+
+#[test]
+fn test_wrong_set_password_falls_back_to_provider() {
+    let mut archive = open_archive(ENCRYPTED_HEADERS);
+    archive.set_password("wrong");
+    let requests = set_answers(&mut archive, &["password"]);
+
+    assert!(matches!(archive.next(), Some(Ok(BlockHeader::Encryption(_)))));
+    assert_eq!(*requests.borrow(), [("the archive headers".to_string(), 1)]);
+}
+
+#[test]
+fn test_gives_up_after_the_attempt_limit() {
+    let mut archive = open_archive(ENCRYPTED_HEADERS);
+    archive.set_max_password_attempts(2);
+    let requests = set_answers(&mut archive, &["wrong", "wrong", "password"]);
+
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("Incorrect password"));
+    assert_eq!(requests.borrow().len(), 2);
+}
+
+#[test]
+fn test_cancel_is_reported() {
+    let mut archive = open_archive(ENCRYPTED_HEADERS);
+    set_answers(&mut archive, &[]);
+
+    let error = archive.next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(PasswordCancelled::is_cause_of(&error));
+}
+
+#[test]
+fn test_provider_is_asked_per_entry() {
+    let mut archive = open_archive(ENCRYPTED_FILES);
+    let requests = set_answers(&mut archive, &["password"]);
+
+    let mut contents = Vec::new();
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            contents.push(archive.read_file_data(&header).unwrap());
+        }
+    }
+    assert_eq!(contents[0], b"Only the right password reveals this.\n");
+    assert_eq!(contents[1].len(), 15498);
+    // The second entry shares the first one's salt, so its keys are reused.
+    assert_eq!(*requests.borrow(), [("'stored.txt'".to_string(), 0)]);
+}
+
+#[test]
+fn test_cancelling_one_entry_leaves_the_archive_readable() {
+    let mut archive = open_archive(ENCRYPTED_FILES);
+    let requests = Requests::default();
+    let recorded = Rc::clone(&requests);
+    archive.set_password_provider(move |request: &PasswordRequest| {
+        recorded.borrow_mut().push((request.target.to_string(), request.failed_attempts));
+        match request.target {
+            PasswordTarget::Entry("stored.txt") => PasswordResponse::Cancel,
+            _ => PasswordResponse::Password("password".into()),
+        }
+    });
+
+    let mut results = Vec::new();
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            results.push(archive.read_file_data(&header).map(|data| data.len()));
+        }
+    }
+    assert!(PasswordCancelled::is_cause_of(results[0].as_ref().unwrap_err()));
+    assert_eq!(*results[1].as_ref().unwrap(), 15498);
+    assert_eq!(requests.borrow().len(), 2);
+}
@@ -0,0 +1,181 @@
+// tests/redirection_tests.rs
+
+use app::extract::Extractor;
+use app::structures::{
+    extra_record::ExtraRecord,
+    file_header::FileHeader,
+    redirection::{Redirection, RedirectionKind},
+};
+use std::fs;
+use std::path::PathBuf;
+
+fn link_header(name: &str, kind: RedirectionKind, target: &str) -> FileHeader {
+    let mut header = FileHeader {
+        file_name: name.to_string(),
+        ..Default::default()
+    };
+    let redirection = Redirection {
+        kind,
+        target: target.to_string(),
+        is_directory: false,
+    };
+    header.set_extra_records(&[ExtraRecord::Redirection(redirection)]).unwrap();
+    header
+}
+
+fn file_header(name: &str) -> FileHeader {
+    FileHeader {
+        file_name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_decode_unix_symlink_record() {
+    // Type 1 (Unix symlink), flags 0, 9-byte target.
+    let data = b"\x01\x00\x09../target";
+    let redirection = Redirection::decode(data).unwrap();
+    assert_eq!(redirection.kind, RedirectionKind::UnixSymlink);
+    assert_eq!(redirection.target, "../target");
+    assert!(!redirection.is_directory);
+    assert_eq!(redirection.encode().unwrap(), data);
+}
+
+#[test]
+fn test_decode_directory_junction_record() {
+    let data = b"\x03\x01\x04C:\\d";
+    let redirection = Redirection::decode(data).unwrap();
+    assert_eq!(redirection.kind, RedirectionKind::WindowsJunction);
+    assert!(redirection.is_directory);
+}
+
+#[test]
+fn test_unknown_redirection_kind_round_trips() {
+    let data = b"\x09\x00\x01x";
+    let redirection = Redirection::decode(data).unwrap();
+    assert_eq!(redirection.kind, RedirectionKind::Other(9));
+    assert_eq!(redirection.encode().unwrap(), data);
+}
+
+#[test]
+fn test_redirection_is_exposed_by_file_header() {
+    let header = link_header("link", RedirectionKind::HardLink, "file.txt");
+    let redirection = header.redirection().unwrap().unwrap();
+    assert_eq!(redirection.kind, RedirectionKind::HardLink);
+    assert_eq!(redirection.target, "file.txt");
+    assert_eq!(file_header("plain").redirection().unwrap(), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_creates_symlink() {
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    extractor.extract(&file_header("data/file.txt"), b"linked").unwrap();
+    let link = extractor
+        .extract(&link_header("data/link", RedirectionKind::UnixSymlink, "file.txt"), b"")
+        .unwrap();
+
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("file.txt"));
+    assert_eq!(fs::read(&link).unwrap(), b"linked");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_creates_hard_link() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    let original = extractor.extract(&file_header("file.txt"), b"shared").unwrap();
+    let link = extractor
+        .extract(&link_header("link.txt", RedirectionKind::HardLink, "file.txt"), b"")
+        .unwrap();
+
+    assert_eq!(fs::metadata(&link).unwrap().ino(), fs::metadata(&original).unwrap().ino());
+}
+
+#[test]
+fn test_extract_copies_data_for_file_copy() {
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    extractor.extract(&file_header("file.txt"), b"copied").unwrap();
+    let copy = extractor
+        .extract(&link_header("copy.txt", RedirectionKind::FileCopy, "file.txt"), b"")
+        .unwrap();
+
+    assert_eq!(fs::read(&copy).unwrap(), b"copied");
+    assert!(!fs::symlink_metadata(&copy).unwrap().file_type().is_symlink());
+}
+
+#[test]
+fn test_extract_refuses_symlink_outside_destination() {
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    for target in ["../outside", "/etc/passwd", "a/../../outside"] {
+        let error = extractor
+            .extract(&link_header("link", RedirectionKind::UnixSymlink, target), b"")
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "target {}", target);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_refuses_chained_symlinks_out_of_destination() {
+    let dir = tempfile::tempdir().unwrap();
+    let extractor = Extractor::new(dir.path());
+    // Each link looks harmless on its own; together `a/b` would be `..`.
+    extractor
+        .extract(&link_header("a", RedirectionKind::UnixSymlink, "."), b"")
+        .unwrap();
+    let error = extractor
+        .extract(&link_header("a/b", RedirectionKind::UnixSymlink, ".."), b"")
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let error = extractor.extract(&file_header("a/file.txt"), b"through a").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // Targets are resolved through the links already on disk.
+    let error = extractor
+        .extract(&link_header("up", RedirectionKind::UnixSymlink, "a/.."), b"")
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    // `..` below a name that does not exist yet depends on what comes later.
+    let error = extractor
+        .extract(&link_header("later", RedirectionKind::UnixSymlink, "m/.."), b"")
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(fs::symlink_metadata(dir.path().join("a/b")).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_extract_does_not_write_through_existing_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let outside = tempfile::tempdir().unwrap();
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.path().join("victim.txt"), b"untouched").unwrap();
+    fs::create_dir_all(&dir).unwrap();
+    std::os::unix::fs::symlink(&outside, dir.path().join("out")).unwrap();
+    std::os::unix::fs::symlink(outside.path().join("victim.txt"), dir.path().join("victim.txt")).unwrap();
+
+    let extractor = Extractor::new(dir.path());
+    for name in ["out/file.txt", "victim.txt"] {
+        let error = extractor.extract(&file_header(name), b"overwritten").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{}", name);
+    }
+    let error = extractor
+        .extract(&link_header("copy.txt", RedirectionKind::FileCopy, "victim.txt"), b"")
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let error = extractor
+        .extract(&link_header("escape", RedirectionKind::UnixSymlink, "out"), b"")
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    assert_eq!(fs::read(outside.path().join("victim.txt")).unwrap(), b"untouched");
+    assert!(!outside.path().join("file.txt").exists());
+    assert!(!dir.path().join("copy.txt").exists());
+}
@@ -0,0 +1,101 @@
+// tests/service_header_tests.rs
+
+use app::archive::Archive;
+use app::encoder::encode_block;
+use app::signature::RAR5_SIGNATURE;
+use app::structures::{
+    base::BlockHeader,
+    block::BaseBlock,
+    file_header::FileHeader,
+    general_block_header::GeneralBlockHeader,
+    service_header::{ServiceHeader, ServiceKind},
+};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+
+fn comment_header(comment: &[u8]) -> ServiceHeader {
+    ServiceHeader {
+        file_header: FileHeader {
+            base: BaseBlock {
+                general_header: GeneralBlockHeader {
+                    data_size: Some(comment.len() as u64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            unpacked_size: Some(comment.len() as u64),
+            file_name: "CMT".to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn test_service_kinds_from_names() {
+    let kinds = ["CMT", "QO", "ACL", "STM", "RR", "XYZ"].map(|name| {
+        let mut header = ServiceHeader::default();
+        header.file_header.file_name = name.to_string();
+        header.kind()
+    });
+    assert_eq!(
+        kinds,
+        [
+            ServiceKind::Comment,
+            ServiceKind::QuickOpen,
+            ServiceKind::Acl,
+            ServiceKind::Stream,
+            ServiceKind::Recovery,
+            ServiceKind::Other("XYZ".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_archive_with_quick_open_record_iterates_to_the_end() {
+    let archive = Archive::new(BufReader::new(File::open("Rar5.rar").unwrap())).unwrap();
+    let blocks: Vec<BlockHeader> = archive.map(|block| block.unwrap()).collect();
+
+    let services: Vec<&ServiceHeader> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            BlockHeader::Service(header) => Some(header),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(services.len(), 1);
+    assert_eq!(services[0].name(), "QO");
+    assert_eq!(services[0].kind(), ServiceKind::QuickOpen);
+    assert!(matches!(blocks.last(), Some(BlockHeader::End(_))));
+}
+
+#[test]
+fn test_entries_after_skipped_service_data_are_read() {
+    let mut archive = Archive::new(BufReader::new(File::open("Rar5.rar").unwrap())).unwrap();
+    let mut files = 0;
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            let data = archive.read_file_data(&header).unwrap();
+            assert_eq!(data.len() as u64, header.unpacked_size.unwrap_or(0));
+            files += 1;
+        }
+    }
+    assert_eq!(files, 6);
+}
+
+#[test]
+fn test_comment_round_trips_through_encoder() {
+    let comment = b"Archive comment";
+    let mut data = RAR5_SIGNATURE.to_vec();
+    encode_block(&BlockHeader::Service(comment_header(comment)), &mut data).unwrap();
+    data.extend_from_slice(comment);
+
+    let mut archive = Archive::new(Cursor::new(data)).unwrap();
+    match archive.next() {
+        Some(Ok(BlockHeader::Service(header))) => {
+            assert_eq!(header.kind(), ServiceKind::Comment);
+            assert_eq!(archive.read_service_data(&header).unwrap(), comment);
+        }
+        other => panic!("Expected a ServiceHeader, but got {:?}", other),
+    }
+    assert!(archive.next().is_none());
+}
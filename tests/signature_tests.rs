@@ -0,0 +1,50 @@
+// tests/signature_tests.rs
+
+use app::archive::Archive;
+use app::decoder::read_signature;
+use app::signature::{detect_format, RarFormat};
+use std::io::{Cursor, ErrorKind};
+
+#[test]
+fn test_detect_format_rar5() {
+    let data = b"Rar!\x1A\x07\x01\x00\x33\x92";
+    assert_eq!(detect_format(data), Some(RarFormat::Rar5));
+}
+
+#[test]
+fn test_detect_format_rar4() {
+    let data = b"Rar!\x1A\x07\x00\xCF\x90";
+    assert_eq!(detect_format(data), Some(RarFormat::Rar4));
+}
+
+#[test]
+fn test_detect_format_rejects_other_data() {
+    assert_eq!(detect_format(b"PK\x03\x04"), None);
+    assert_eq!(detect_format(b"Rar!\x1A\x07\x02\x00"), None);
+    assert_eq!(detect_format(b"Rar!"), None);
+}
+
+#[test]
+fn test_read_signature_consumes_only_the_signature() {
+    let mut cursor = Cursor::new(b"Rar!\x1A\x07\x00\xCF".to_vec());
+    assert_eq!(read_signature(&mut cursor).unwrap(), RarFormat::Rar4);
+    assert_eq!(cursor.position(), 7);
+}
+
+#[test]
+fn test_archive_new_rejects_non_rar_data() {
+    let error = Archive::new(Cursor::new(b"PK\x03\x04 not a rar file".to_vec())).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_archive_new_rejects_truncated_signature() {
+    let error = Archive::new(Cursor::new(b"Rar!".to_vec())).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_archive_new_rejects_rar4_archives() {
+    let error = Archive::new(Cursor::new(b"Rar!\x1A\x07\x00\xCF\x90\x73".to_vec())).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Unsupported);
+}
@@ -0,0 +1,174 @@
+// tests/solid_archive_tests.rs
+
+mod common;
+
+use app::archive::Archive;
+use app::indexed_archive::IndexedArchive;
+use app::password::{PasswordRequest, PasswordResponse};
+use app::signature::RAR5_SIGNATURE;
+use app::structures::{
+    base::BlockHeader,
+    block::{BaseBlock, Block},
+    compression_info::CompressionInfo,
+    extra_record::ExtraRecord,
+    file_encryption::FileEncryption,
+    file_header::FileHeader,
+    general_block_header::GeneralBlockHeader,
+    main_archive_header::MainArchiveHeader,
+};
+use std::cell::Cell;
+use std::io::{Cursor, ErrorKind};
+use std::rc::Rc;
+
+use common::{literal, lz_stream};
+
+/// Compression info for method 3 with a 128 KiB dictionary.
+const COMPRESSED: CompressionInfo = CompressionInfo(3 << 7);
+const SOLID_COMPRESSED: CompressionInfo = CompressionInfo((3 << 7) | 0x40);
+
+fn build_block<B: Block>(block: &mut B) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    block.encode(&mut buffer).unwrap();
+    buffer
+}
+
+fn file_block(name: &str, compression_info: CompressionInfo, data: &[u8]) -> Vec<u8> {
+    file_block_with_records(name, compression_info, &[], data)
+}
+
+fn file_block_with_records(
+    name: &str,
+    compression_info: CompressionInfo,
+    records: &[ExtraRecord],
+    data: &[u8],
+) -> Vec<u8> {
+    let mut file_header = FileHeader {
+        base: BaseBlock {
+            general_header: GeneralBlockHeader {
+                data_size: Some(data.len() as u64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        compression_info,
+        file_name: name.to_string(),
+        ..Default::default()
+    };
+    file_header.set_extra_records(records).unwrap();
+    let mut block = build_block(&mut file_header);
+    block.extend_from_slice(data);
+    block
+}
+
+/// A solid archive whose second entry is a match into the first entry's data.
+fn solid_archive() -> Vec<u8> {
+    let first = lz_stream(&[literal(b'a'), literal(b'b'), literal(b'c')]);
+    solid_archive_after(file_block("first", COMPRESSED, &first))
+}
+
+/// A solid archive holding `first_block` and then a solid entry whose data is
+/// a match into the first entry's data.
+fn solid_archive_after(first_block: Vec<u8>) -> Vec<u8> {
+    // Main symbol 262 + 1 is a match of length 3; distance slot 2 is distance 3.
+    let second = lz_stream(&[(263, 9), (2, 6), literal(b'd')]);
+
+    let mut data = RAR5_SIGNATURE.to_vec();
+    let mut main_header = MainArchiveHeader {
+        archive_flags: 0x0004,
+        ..Default::default()
+    };
+    data.extend_from_slice(&build_block(&mut main_header));
+    data.extend_from_slice(&first_block);
+    data.extend_from_slice(&file_block("second", SOLID_COMPRESSED, &second));
+    data
+}
+
+fn next_file(archive: &mut Archive<Cursor<Vec<u8>>>) -> FileHeader {
+    loop {
+        match archive.next() {
+            Some(Ok(BlockHeader::File(header))) => return header,
+            Some(Ok(_)) => continue,
+            other => panic!("Expected a FileHeader, but got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_solid_entries_share_dictionary() {
+    let mut archive = Archive::new(Cursor::new(solid_archive())).unwrap();
+
+    let first = next_file(&mut archive);
+    assert!(!first.is_solid());
+    assert_eq!(archive.read_file_data(&first).unwrap(), b"abc");
+
+    let second = next_file(&mut archive);
+    assert!(second.is_solid());
+    assert_eq!(archive.read_file_data(&second).unwrap(), b"abcd");
+}
+
+#[test]
+fn test_skipped_solid_entry_is_decoded_in_the_background() {
+    let mut archive = Archive::new(Cursor::new(solid_archive())).unwrap();
+
+    next_file(&mut archive);
+    let second = next_file(&mut archive);
+    assert_eq!(archive.read_file_data(&second).unwrap(), b"abcd");
+}
+
+#[test]
+fn test_corrupt_skipped_solid_entry_is_reported_for_the_next_one() {
+    let corrupt = file_block("first", COMPRESSED, &[0xFF; 8]);
+    let mut archive = Archive::new(Cursor::new(solid_archive_after(corrupt))).unwrap();
+
+    next_file(&mut archive);
+    let second = next_file(&mut archive);
+    let error = archive.read_file_data(&second).unwrap_err();
+    assert!(error.to_string().contains("skipped entry 'first'"), "{}", error);
+}
+
+#[test]
+fn test_skipped_encrypted_solid_entry_does_not_ask_for_a_password() {
+    let encryption = ExtraRecord::FileEncryption(FileEncryption {
+        kdf_count: 1,
+        ..Default::default()
+    });
+    let first = file_block_with_records("first", COMPRESSED, &[encryption], &[0u8; 16]);
+    let mut archive = Archive::new(Cursor::new(solid_archive_after(first))).unwrap();
+    let requests = Rc::new(Cell::new(0));
+    let counted = Rc::clone(&requests);
+    archive.set_password_provider(move |_: &PasswordRequest| {
+        counted.set(counted.get() + 1);
+        PasswordResponse::Cancel
+    });
+
+    next_file(&mut archive);
+    let second = next_file(&mut archive);
+    let error = archive.read_file_data(&second).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(error.to_string().contains("skipped entry 'first'"), "{}", error);
+    assert_eq!(requests.get(), 0);
+}
+
+#[test]
+fn test_solid_entry_without_predecessor_is_refused() {
+    let second = lz_stream(&[(263, 9), (2, 6)]);
+    let mut data = RAR5_SIGNATURE.to_vec();
+    data.extend_from_slice(&file_block("second", SOLID_COMPRESSED, &second));
+
+    let mut archive = Archive::new(Cursor::new(data)).unwrap();
+    let header = next_file(&mut archive);
+    let error = archive.read_file_data(&header).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_indexed_archive_decodes_solid_entries_in_order() {
+    let mut archive = IndexedArchive::new(Cursor::new(solid_archive())).unwrap();
+    assert_eq!(archive.entries().len(), 2);
+
+    // Straight to the second entry: the first is decoded on the way.
+    assert_eq!(archive.extract_by_index(1).unwrap(), b"abcd");
+    assert_eq!(archive.extract_by_index(0).unwrap(), b"abc");
+    // The decoder now follows the first entry and can go on from there.
+    assert_eq!(archive.extract_by_name("second").unwrap(), b"abcd");
+}
@@ -0,0 +1,95 @@
+// tests/unix_owner_tests.rs
+
+use app::extract::Extractor;
+use app::structures::{extra_record::ExtraRecord, file_header::FileHeader, unix_owner::UnixOwner};
+use std::fs;
+
+fn owned_header(name: &str, owner: UnixOwner) -> FileHeader {
+    let mut header = FileHeader {
+        file_name: name.to_string(),
+        ..Default::default()
+    };
+    header.set_extra_records(&[ExtraRecord::UnixOwner(owner)]).unwrap();
+    header
+}
+
+#[test]
+fn test_decode_owner_with_names_and_ids() {
+    let data = b"\x0F\x04user\x05staff\xE8\x07\x14";
+    let owner = UnixOwner::decode(data).unwrap();
+    assert_eq!(
+        owner,
+        UnixOwner {
+            user_name: Some("user".to_string()),
+            group_name: Some("staff".to_string()),
+            user_id: Some(1000),
+            group_id: Some(20),
+        }
+    );
+    assert_eq!(owner.encode().unwrap(), data);
+}
+
+#[test]
+fn test_decode_owner_with_group_only() {
+    let data = b"\x02\x05wheel";
+    let owner = UnixOwner::decode(data).unwrap();
+    assert_eq!(owner.user_name, None);
+    assert_eq!(owner.group_name.as_deref(), Some("wheel"));
+    assert_eq!(owner.encode().unwrap(), data);
+}
+
+#[test]
+fn test_owner_is_exposed_by_file_header() {
+    let owner = UnixOwner {
+        user_name: Some("backup".to_string()),
+        ..Default::default()
+    };
+    let header = owned_header("file.txt", owner.clone());
+    assert_eq!(header.unix_owner().unwrap(), Some(owner));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_owner_is_only_restored_when_enabled() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let owner = UnixOwner {
+        user_id: Some(4242),
+        group_id: Some(4343),
+        ..Default::default()
+    };
+    let path = Extractor::new(dir.path()).extract(&owned_header("file.txt", owner), b"").unwrap();
+    assert_ne!(fs::metadata(&path).unwrap().uid(), 4242);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_restore_owner_prefers_names_over_ids() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut extractor = Extractor::new(dir.path());
+    extractor.set_restore_owner(true);
+
+    let by_name = UnixOwner {
+        user_name: Some("root".to_string()),
+        user_id: Some(4242),
+        ..Default::default()
+    };
+    let by_id = UnixOwner {
+        user_name: Some("no-such-user-for-rar-tests".to_string()),
+        user_id: Some(4242),
+        group_id: Some(4343),
+        ..Default::default()
+    };
+    let named = extractor.extract(&owned_header("named.txt", by_name), b"").unwrap();
+    let numbered = extractor.extract(&owned_header("numbered.txt", by_id), b"").unwrap();
+
+    // Without privileges the files keep the current owner.
+    if fs::metadata(&dir).unwrap().uid() == 0 {
+        assert_eq!(fs::metadata(&named).unwrap().uid(), 0);
+        let metadata = fs::metadata(&numbered).unwrap();
+        assert_eq!((metadata.uid(), metadata.gid()), (4242, 4343));
+    }
+}
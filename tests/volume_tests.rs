@@ -0,0 +1,100 @@
+// tests/volume_tests.rs
+
+use app::archive::Archive;
+use app::checksum::crc32;
+use app::structures::base::BlockHeader;
+use app::volume::FileVolumes;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+
+/// Three volumes: `first.txt` in the first, `split.bin` compressed and split
+/// across all three, and `last.txt` in the third.
+///
+/// Not made by rar: `tests/assets/scripts/multi_volume.py` writes them from
+/// the RAR 5.0 technote, independently of this crate, with
+/// `python3 tests/assets/scripts/multi_volume.py`; only the packed data of
+/// `split.bin` comes from rar. libarchive (`bsdtar -tvf`) reads the first
+/// volume. They should be replaced by the output of
+/// `rar a -v1k multi.rar first.txt split.bin last.txt`.
+const FIRST_VOLUME: &str = "tests/assets/multi.part1.rar";
+
+#[test]
+fn test_lists_each_entry_once() {
+    let archive = Archive::from_volumes(FileVolumes::new(FIRST_VOLUME)).unwrap();
+    let blocks: Vec<BlockHeader> = archive.map(|block| block.unwrap()).collect();
+    let names: Vec<&str> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            BlockHeader::File(header) => Some(header.file_name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, ["first.txt", "split.bin", "last.txt"]);
+    assert!(matches!(blocks.first(), Some(BlockHeader::Main(header)) if header.is_volume()));
+    assert!(matches!(blocks.last(), Some(BlockHeader::End(header)) if header.is_last_volume()));
+    assert_eq!(blocks.len(), 5);
+}
+
+#[test]
+fn test_joins_data_split_across_volumes() {
+    let mut archive = Archive::from_volumes(FileVolumes::new(FIRST_VOLUME)).unwrap();
+    let mut contents = Vec::new();
+    while let Some(block) = archive.next() {
+        if let BlockHeader::File(header) = block.unwrap() {
+            contents.push(archive.read_file_data(&header).unwrap());
+        }
+    }
+    assert_eq!(contents[0], b"The first entry fits in the first volume.\n");
+    assert_eq!(contents[1].len(), 15498);
+    assert_eq!(crc32(&contents[1]), 0x9bd160fa);
+    assert_eq!(contents[2], b"The last entry follows the split one.\n");
+}
+
+#[test]
+fn test_skipping_a_split_entry_reaches_the_next_one() {
+    let mut archive = Archive::from_volumes(FileVolumes::new(FIRST_VOLUME)).unwrap();
+    let header = loop {
+        match archive.next() {
+            Some(Ok(BlockHeader::File(header))) if header.file_name == "last.txt" => break header,
+            Some(Ok(_)) => continue,
+            other => panic!("Expected last.txt, but got {:?}", other),
+        }
+    };
+    let data = archive.read_file_data(&header).unwrap();
+    assert_eq!(data, b"The last entry follows the split one.\n");
+}
+
+#[test]
+fn test_missing_volume_is_named() {
+    let dir = tempfile::tempdir().unwrap();
+    for part in ["multi.part1.rar", "multi.part2.rar"] {
+        fs::copy(format!("tests/assets/{}", part), dir.path().join(part)).unwrap();
+    }
+    let mut archive = Archive::from_volumes(FileVolumes::new(dir.path().join("multi.part1.rar"))).unwrap();
+    let error = archive.find_map(|block| block.err()).unwrap();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert!(error.to_string().contains("multi.part3.rar"), "{}", error);
+}
+
+#[test]
+fn test_single_volume_reader_reports_the_volume_end() {
+    let file = File::open(FIRST_VOLUME).unwrap();
+    let archive = Archive::new(BufReader::new(file)).unwrap();
+    let last = archive.map(|block| block.unwrap()).last();
+    assert!(matches!(last, Some(BlockHeader::VolumeEnd(header)) if !header.is_last_volume()));
+}
+
+#[test]
+fn test_split_data_needs_a_volume_source() {
+    let file = File::open(FIRST_VOLUME).unwrap();
+    let mut archive = Archive::new(BufReader::new(file)).unwrap();
+    let header = loop {
+        match archive.next() {
+            Some(Ok(BlockHeader::File(header))) if header.file_name == "split.bin" => break header,
+            Some(Ok(_)) => continue,
+            other => panic!("Expected split.bin, but got {:?}", other),
+        }
+    };
+    let error = archive.read_file_data(&header).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Unsupported);
+}